@@ -4,6 +4,8 @@ import io.ktor.client.HttpClient
 import kotlinx.coroutines.CoroutineScope
 import kotlinx.coroutines.SupervisorJob
 import kotlinx.coroutines.cancel
+import kotlinx.coroutines.coroutineScope
+import kotlinx.coroutines.launch
 import kotlinx.serialization.json.Json
 import me.devnatan.dockerkt.io.createHttpClient
 import me.devnatan.dockerkt.resource.container.ContainerResource
@@ -23,16 +25,34 @@ public class DockerClient(
     override val coroutineContext: CoroutineContext = SupervisorJob()
 
     public val json: Json get() = DockerKotlinJson
+    internal val metricsRecorder: MetricsRecorder = MetricsRecorder()
+    internal val cookieJar: CookieJar? = if (config.cookies) CookieJar() else null
     public val httpClient: HttpClient = createHttpClient(this)
 
     public val images: ImageResource = ImageResource(httpClient, json)
     public val exec: ExecResource = ExecResource(httpClient)
-    public val containers: ContainerResource = ContainerResource(httpClient, json)
+    public val containers: ContainerResource = ContainerResource(httpClient, json, config.warningHandler)
     public val networks: NetworkResource = NetworkResource(httpClient, json)
     public val volumes: VolumeResource = VolumeResource(httpClient, json)
     public val secrets: SecretResource = SecretResource(httpClient, json)
     public val system: SystemResource = SystemResource(httpClient, json)
 
+    /**
+     * Establishes connections to the Docker daemon ahead of time, completing the TLS handshake if it's enabled, so
+     * that the first calls made afterward don't pay the connection setup cost.
+     *
+     * Connections are only kept for reuse on the JVM, where at most 5 idle connections are kept at a time.
+     *
+     * @param connections Amount of connections to establish.
+     */
+    public suspend fun prewarm(connections: Int = 1) {
+        require(connections > 0) { "Connections must be positive" }
+        coroutineScope {
+            // concurrent calls so that each one needs its own connection
+            repeat(connections) { launch { system.ping() } }
+        }
+    }
+
     override fun close() {
         cancel()
         httpClient.close()
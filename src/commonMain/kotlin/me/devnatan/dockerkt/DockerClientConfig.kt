@@ -1,10 +1,24 @@
 package me.devnatan.dockerkt
 
+import me.devnatan.dockerkt.io.CircuitBreaker
 import me.devnatan.dockerkt.io.DefaultDockerHttpSocket
 import me.devnatan.dockerkt.io.DefaultDockerUnixSocket
+import me.devnatan.dockerkt.io.DefaultRequestIdHeader
+import me.devnatan.dockerkt.io.FaultInjection
+import me.devnatan.dockerkt.io.HttpFixtures
 import me.devnatan.dockerkt.io.HttpSocketPrefix
+import me.devnatan.dockerkt.io.ProxyConfig
+import me.devnatan.dockerkt.io.RedirectPolicy
+import me.devnatan.dockerkt.io.RetryPolicy
+import me.devnatan.dockerkt.io.SocketOptions
+import me.devnatan.dockerkt.io.Timeouts
+import me.devnatan.dockerkt.io.TraceContext
+import me.devnatan.dockerkt.io.TracePropagation
+import me.devnatan.dockerkt.io.TrafficDump
 import me.devnatan.dockerkt.io.UnixSocketPrefix
+import me.devnatan.dockerkt.io.environmentProxyFor
 import kotlin.jvm.JvmStatic
+import kotlin.time.Duration
 
 internal val DefaultDockerClientConfig = DockerClientConfig.builder().forCurrentPlatform().build()
 
@@ -23,6 +37,11 @@ private const val DockerApiVersionEnvKey = "DOCKER_API_VERSION"
  */
 public const val DefaultDockerApiVersion: String = "1.41"
 
+/**
+ * User agent sent by docker-kotlin by default.
+ */
+public const val DefaultUserAgent: String = "docker-kotlin"
+
 /**
  * Class to store all Docker client configurations.
  *
@@ -31,12 +50,66 @@ public const val DefaultDockerApiVersion: String = "1.41"
  *                   will try to select the socket path based on current operating system.
  * @param apiVersion The version of the Docker API that will be used during communication.
  *                   See more: [Versioned API and SDK](https://docs.docker.com/engine/api/#versioned-api-and-sdk).
+ * @param debugHttpCalls Whether to log the HTTP calls to the Docker daemon.
+ * @param httpFixtures Records HTTP calls to, or replays them from, a cassette file. See [HttpFixtures].
+ * @param faultInjection Faults injected in the HTTP calls for resilience testing. See [FaultInjection].
+ * @param dryRun Whether requests should be built but never sent, failing with [DockerDryRunException] instead.
+ * @param tls TLS configuration used to connect to a Docker daemon exposed over TCP, or `null` to not use TLS.
+ * @param tracePropagation Propagation of W3C trace context headers on every call, or `null` to not propagate it.
+ * @param circuitBreaker Makes calls fail fast while the Docker daemon is unreachable. See [CircuitBreaker].
+ * @param requestIdHeader Name of the header a unique id is sent in on every call, or `null` to not send one.
+ * @param socketOptions Low-level options of the sockets used to connect to a Docker daemon. See [SocketOptions].
+ * @param trafficDump Writes the HTTP calls to a file, with credentials redacted, for debugging. See [TrafficDump].
+ * @param warningHandler Handles the warnings reported by the Docker daemon, like the use of deprecated options.
+ * @param timeouts Connect, read idle and total timeouts of the HTTP calls, none by default. See [Timeouts].
+ * @param proxy Proxy the Docker daemon is reached through when exposed over TCP, or `null` to connect directly. See [ProxyConfig].
+ * @param decompressResponses Whether compressed response bodies are requested and transparently decoded.
+ * @param responseListener Called with the details of every response received from the Docker daemon. See [DockerResponseInfo].
+ * @param forceHttp1 Whether HTTP/1.1 is always used, instead of negotiating HTTP/2 on TLS connections where supported.
+ * @param retryPolicy Retries idempotent calls that failed due to a transient condition, or `null` to not retry. See [RetryPolicy].
+ * @param userAgent Value of the `User-Agent` header sent on every call.
+ * @param defaultHeaders Headers sent on every call, unless the call sets a header with the same name itself.
+ * @param callTimings Whether the time spent on each phase of a call is measured and reported to the [responseListener].
+ * @param redirectPolicy Which redirects are followed, up to 20 to any host by default. See [RedirectPolicy].
+ * @param cookies Whether cookies set by the Docker daemon, or a reverse proxy in front of it, are stored and sent back.
+ * @param responseCache Whether responses are cached in memory following their HTTP caching headers.
  */
-public class DockerClientConfig(
+public class DockerClientConfig internal constructor(
     public val socketPath: String,
     public val apiVersion: String,
     public val debugHttpCalls: Boolean,
+    public val httpFixtures: HttpFixtures? = null,
+    public val faultInjection: FaultInjection? = null,
+    public val dryRun: Boolean = false,
+    public val tls: DockerTlsConfig? = null,
+    public val tracePropagation: TracePropagation? = null,
+    public val circuitBreaker: CircuitBreaker? = null,
+    public val requestIdHeader: String? = null,
+    public val socketOptions: SocketOptions? = null,
+    public val trafficDump: TrafficDump? = null,
+    public val warningHandler: (DockerWarning) -> Unit = DefaultDockerWarningHandler,
+    public val timeouts: Timeouts = Timeouts(),
+    public val proxy: ProxyConfig? = null,
+    public val decompressResponses: Boolean = false,
+    public val responseListener: ((DockerResponseInfo) -> Unit)? = null,
+    public val forceHttp1: Boolean = false,
+    public val retryPolicy: RetryPolicy? = null,
+    public val userAgent: String = DefaultUserAgent,
+    public val defaultHeaders: Map<String, List<String>> = emptyMap(),
+    public val callTimings: Boolean = false,
+    public val redirectPolicy: RedirectPolicy = RedirectPolicy(),
+    public val cookies: Boolean = false,
+    public val responseCache: Boolean = false,
 ) {
+    /**
+     * Creates a configuration with every other option set to its default, use [builder] to set them.
+     */
+    public constructor(
+        socketPath: String,
+        apiVersion: String,
+        debugHttpCalls: Boolean,
+    ) : this(socketPath, apiVersion, debugHttpCalls, httpFixtures = null)
+
     init {
         check(socketPath.isNotBlank()) { "Socket path must be provided and cannot be blank" }
         check(apiVersion.isNotBlank()) { "Docker Remote API version must be provided and cannot be blank" }
@@ -72,6 +145,121 @@ public class DockerClientConfigBuilder {
      */
     private var debugHttpCalls: Boolean = false
 
+    /**
+     * Records HTTP calls to, or replays them from, a cassette file.
+     */
+    private var httpFixtures: HttpFixtures? = null
+
+    /**
+     * Faults injected in the HTTP calls for resilience testing.
+     */
+    private var faultInjection: FaultInjection? = null
+
+    /**
+     * Whether requests should be built but never sent.
+     */
+    private var dryRun: Boolean = false
+
+    /**
+     * TLS configuration used to connect to a Docker daemon exposed over TCP.
+     */
+    private var tls: DockerTlsConfig? = null
+
+    /**
+     * Propagation of W3C trace context headers on every call.
+     */
+    private var tracePropagation: TracePropagation? = null
+
+    /**
+     * Circuit breaker that makes calls fail fast while the Docker daemon is unreachable.
+     */
+    private var circuitBreaker: CircuitBreaker? = null
+
+    /**
+     * Name of the header a unique id is sent in on every call.
+     */
+    private var requestIdHeader: String? = null
+
+    /**
+     * Low-level options of the sockets used to connect to a Docker daemon.
+     */
+    private var socketOptions: SocketOptions? = null
+
+    /**
+     * Writes the HTTP calls to a file, with credentials redacted, for debugging.
+     */
+    private var trafficDump: TrafficDump? = null
+
+    /**
+     * Handles the warnings reported by the Docker daemon.
+     */
+    private var warningHandler: (DockerWarning) -> Unit = DefaultDockerWarningHandler
+
+    /**
+     * Connect, read idle and total timeouts of the HTTP calls.
+     */
+    private var timeouts: Timeouts = Timeouts()
+
+    /**
+     * Proxy the Docker daemon is reached through when exposed over TCP.
+     */
+    private var proxy: ProxyConfig? = null
+
+    /**
+     * Whether the proxy is read from the environment, until one is set explicitly.
+     */
+    private var proxyFromEnvironment: Boolean = true
+
+    /**
+     * Whether compressed response bodies are requested and transparently decoded.
+     */
+    private var decompressResponses: Boolean = false
+
+    /**
+     * Called with the details of every response received from the Docker daemon.
+     */
+    private var responseListener: ((DockerResponseInfo) -> Unit)? = null
+
+    /**
+     * Whether HTTP/1.1 is always used, instead of negotiating HTTP/2 on TLS connections.
+     */
+    private var forceHttp1: Boolean = false
+
+    /**
+     * Retries idempotent calls that failed due to a transient condition.
+     */
+    private var retryPolicy: RetryPolicy? = null
+
+    /**
+     * Value of the `User-Agent` header sent on every call.
+     */
+    private var userAgent: String = DefaultUserAgent
+
+    /**
+     * Headers sent on every call, unless the call sets a header with the same name itself.
+     */
+    private var defaultHeaders: Map<String, List<String>> = emptyMap()
+
+    /**
+     * Whether the time spent on each phase of a call is measured.
+     */
+    private var callTimings: Boolean = false
+
+    /**
+     * Which redirects are followed.
+     */
+    private var redirectPolicy: RedirectPolicy = RedirectPolicy()
+
+    /**
+     * Whether cookies set by the Docker daemon are stored and sent back.
+     */
+    private var cookies: Boolean = false
+
+    /**
+     * Whether responses are cached in memory following their HTTP caching headers.
+     */
+    private var responseCache: Boolean = false
+
     /**
      * Sets the Docker socket path.
      *
@@ -102,6 +290,293 @@ public class DockerClientConfigBuilder {
         return this
     }
 
+    /**
+     * Sets the HTTP fixtures mode, used to record HTTP calls to a cassette file and replay them later
+     * without a Docker daemon.
+     *
+     * @param httpFixtures The fixtures mode, or `null` to disable it.
+     */
+    public fun httpFixtures(httpFixtures: HttpFixtures?): DockerClientConfigBuilder {
+        this.httpFixtures = httpFixtures
+        return this
+    }
+
+    /**
+     * Sets the faults injected in the HTTP calls, so that retry and reconnection behavior can be tested.
+     *
+     * @param faultInjection The faults to inject, or `null` to disable fault injection.
+     */
+    public fun faultInjection(faultInjection: FaultInjection?): DockerClientConfigBuilder {
+        this.faultInjection = faultInjection
+        return this
+    }
+
+    /**
+     * Sets the dry-run mode, in which requests are fully built but never sent to the Docker daemon. Every call fails
+     * with a [DockerDryRunException] holding the request that would have been sent instead.
+     *
+     * @param dryRun Whether to enable the dry-run mode.
+     */
+    public fun dryRun(dryRun: Boolean = true): DockerClientConfigBuilder {
+        this.dryRun = dryRun
+        return this
+    }
+
+    /**
+     * Sets the TLS configuration used to connect to a Docker daemon exposed over TCP.
+     *
+     * @param tls The TLS configuration, or `null` to not use TLS.
+     */
+    public fun tls(tls: DockerTlsConfig?): DockerClientConfigBuilder {
+        this.tls = tls
+        return this
+    }
+
+    /**
+     * Configures the TLS connection to a Docker daemon exposed over TCP.
+     *
+     * @param block The TLS configuration.
+     */
+    public fun tls(block: DockerTlsConfigBuilder.() -> Unit): DockerClientConfigBuilder =
+        tls(DockerTlsConfigBuilder().apply(block).build())
+
+    /**
+     * Sets the propagation of W3C trace context (`traceparent` and `tracestate`) headers on every call, so that
+     * Docker API calls show up as part of distributed traces.
+     *
+     * @param tracePropagation The trace propagation, or `null` to disable it.
+     */
+    public fun tracePropagation(tracePropagation: TracePropagation?): DockerClientConfigBuilder {
+        this.tracePropagation = tracePropagation
+        return this
+    }
+
+    /**
+     * Enables the propagation of W3C trace context headers on every call.
+     *
+     * @param parentContext Provides the context of the span calls are made from, e.g. the current OpenTelemetry span.
+     */
+    public fun tracePropagation(parentContext: () -> TraceContext? = { null }): DockerClientConfigBuilder =
+        tracePropagation(TracePropagation(parentContext))
+
+    /**
+     * Sets the circuit breaker that makes calls fail fast with a [DockerCircuitOpenException] while the Docker daemon
+     * is unreachable, instead of having every call wait for its own connection to fail.
+     *
+     * @param circuitBreaker The circuit breaker, or `null` to disable it.
+     */
+    public fun circuitBreaker(circuitBreaker: CircuitBreaker?): DockerClientConfigBuilder {
+        this.circuitBreaker = circuitBreaker
+        return this
+    }
+
+    /**
+     * Sets the name of the header a unique id is sent in on every call, so that calls can be correlated across logs.
+     * The id of a call is available in [DockerResponseException.requestId], [DockerResponseInfo.requestId] and
+     * [DockerWarning.requestId], and the metrics of the client keep the id of a request of each latency range in
+     * [LatencyHistogram.exemplarRequestIds].
+     *
+     * @param headerName The header name, or `null` to not send request ids.
+     */
+    public fun requestIds(headerName: String? = DefaultRequestIdHeader): DockerClientConfigBuilder {
+        this.requestIdHeader = headerName
+        return this
+    }
+
+    /**
+     * Sets low-level options of the sockets used to connect to a Docker daemon, like keepalive or buffer sizes.
+     *
+     * @param socketOptions The socket options, or `null` to keep the operating system defaults.
+     */
+    public fun socketOptions(socketOptions: SocketOptions?): DockerClientConfigBuilder {
+        this.socketOptions = socketOptions
+        return this
+    }
+
+    /**
+     * Sets the dump of the HTTP calls to a rotating file, with credentials redacted, to diagnose protocol
+     * disagreements with the Docker daemon.
+     *
+     * @param trafficDump The traffic dump, or `null` to disable it.
+     */
+    public fun trafficDump(trafficDump: TrafficDump?): DockerClientConfigBuilder {
+        this.trafficDump = trafficDump
+        return this
+    }
+
+    /**
+     * Sets the handler of the warnings reported by the Docker daemon, either in `Warning` response headers or in the
+     * response body, like the use of deprecated options or fields that will be removed in a future API version.
+     * Warnings are logged by [DefaultDockerWarningHandler] by default.
+     *
+     * @param warningHandler The warning handler.
+     */
+    public fun warningHandler(warningHandler: (DockerWarning) -> Unit): DockerClientConfigBuilder {
+        this.warningHandler = warningHandler
+        return this
+    }
+
+    /**
+     * Sets the timeouts of the HTTP calls made to the Docker daemon, where [Duration.ZERO] means no timeout.
+     *
+     * @param connect Maximum time to establish a connection.
+     * @param readIdle Maximum time without receiving any data while waiting for or reading a response.
+     * @param total Maximum time of a whole call, streams included.
+     */
+    public fun timeouts(
+        connect: Duration = Duration.ZERO,
+        readIdle: Duration = Duration.ZERO,
+        total: Duration = Duration.ZERO,
+    ): DockerClientConfigBuilder {
+        this.timeouts = Timeouts(connect, readIdle, total)
+        return this
+    }
+
+    /**
+     * Sets the proxy the Docker daemon is reached through when it is exposed over TCP.
+     *
+     * By default, the proxy is read from the `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment variables, and
+     * ignored with a warning if they don't hold a valid proxy.
+     *
+     * @param proxy The proxy, or `null` to always connect directly.
+     */
+    public fun proxy(proxy: ProxyConfig?): DockerClientConfigBuilder {
+        this.proxy = proxy
+        this.proxyFromEnvironment = false
+        return this
+    }
+
+    /**
+     * Enables gzip and deflate compression of the response bodies, which are decoded before being parsed. The
+     * original encoding of a response is available in [DockerResponseInfo.contentEncoding] and, for failed calls, in
+     * [DockerResponseException.contentEncoding].
+     *
+     * Zstd is not decoded, so it's never requested: a zstd encoded body sent anyway is read as it is.
+     *
+     * Useful when a remote daemon is reached through a reverse proxy that compresses responses, e.g. over a slow link.
+     *
+     * @param decompress Whether to request compressed responses.
+     */
+    public fun decompressResponses(decompress: Boolean = true): DockerClientConfigBuilder {
+        this.decompressResponses = decompress
+        return this
+    }
+
+    /**
+     * Sets a listener called with the details of every response received from the Docker daemon, like the negotiated
+     * HTTP version, for diagnostics. Error responses are reported as well, before being thrown.
+     *
+     * @param responseListener The response listener, or `null` to not listen to responses.
+     */
+    public fun responseListener(responseListener: ((DockerResponseInfo) -> Unit)?): DockerClientConfigBuilder {
+        this.responseListener = responseListener
+        return this
+    }
+
+    /**
+     * Forces HTTP/1.1 to be used on every connection.
+     *
+     * By default HTTP/2 is negotiated through ALPN on TLS connections to daemons that support it, on the JVM. Other
+     * platforms always use HTTP/1.1. Connections that are hijacked to stream raw data, e.g. by exec and attach, can
+     * only be upgraded over HTTP/1.1.
+     *
+     * @param force Whether to force HTTP/1.1.
+     */
+    public fun forceHttp1(force: Boolean = true): DockerClientConfigBuilder {
+        this.forceHttp1 = force
+        return this
+    }
+
+    /**
+     * Sets the policy used to retry idempotent calls that failed due to a transient condition, like connection errors
+     * or `503 Service Unavailable` responses while the Docker daemon is restarting.
+     *
+     * @param retryPolicy The retry policy, or `null` to not retry calls.
+     */
+    public fun retryPolicy(retryPolicy: RetryPolicy?): DockerClientConfigBuilder {
+        this.retryPolicy = retryPolicy
+        return this
+    }
+
+    /**
+     * Sets the value of the `User-Agent` header sent on every call, e.g. to identify the embedding application in the
+     * Docker daemon logs.
+     *
+     * @param userAgent The user agent.
+     */
+    public fun userAgent(userAgent: String): DockerClientConfigBuilder {
+        this.userAgent = userAgent
+        return this
+    }
+
+    /**
+     * Adds a header sent on every call, like `X-Registry-Auth` or the credentials of a reverse proxy in front of the
+     * Docker daemon. Calls that set a header with the same name themselves take precedence.
+     *
+     * Adding a header with the same name more than once sends all of its values.
+     *
+     * @param name The header name.
+     * @param value The header value.
+     */
+    public fun defaultHeader(
+        name: String,
+        value: String,
+    ): DockerClientConfigBuilder {
+        this.defaultHeaders += name to defaultHeaders[name].orEmpty() + value
+        return this
+    }
+
+    /**
+     * Measures the time spent on each phase of every call, like name resolution, connection and TLS handshake, and
+     * reports it in [DockerResponseInfo.timings] to the response listener.
+     *
+     * @param callTimings Whether to measure call timings.
+     */
+    public fun callTimings(callTimings: Boolean = true): DockerClientConfigBuilder {
+        this.callTimings = callTimings
+        return this
+    }
+
+    /**
+     * Sets which redirects sent by the Docker daemon, or a reverse proxy in front of it, are followed. The URLs a call
+     * was redirected from are available in [DockerResponseInfo.redirects].
+     *
+     * @param redirectPolicy The redirect policy, e.g. [RedirectPolicy.None] to never follow redirects.
+     */
+    public fun redirectPolicy(redirectPolicy: RedirectPolicy): DockerClientConfigBuilder {
+        this.redirectPolicy = redirectPolicy
+        return this
+    }
+
+    /**
+     * Stores the cookies set by the Docker daemon, or a reverse proxy in front of it, and sends them back on the next
+     * calls, e.g. for authenticating proxies that keep a session cookie. Stored cookies can be inspected with
+     * [DockerClient.cookies] and removed with [DockerClient.clearCookies].
+     *
+     * @param cookies Whether to store cookies.
+     */
+    public fun cookies(cookies: Boolean = true): DockerClientConfigBuilder {
+        this.cookies = cookies
+        return this
+    }
+
+    /**
+     * Caches responses in memory following their HTTP caching headers. Responses that are fresh according to their
+     * `Cache-Control: max-age` or `Expires` headers are served from the cache without calling the Docker daemon at
+     * all. Responses that carry an `ETag` or `Last-Modified` header are revalidated with `If-None-Match` and
+     * `If-Modified-Since` on the next calls to the same URL, and the ones the Docker daemon answers with
+     * `304 Not Modified` are served from the cache and reported as such in [DockerResponseInfo.fromCache].
+     *
+     * The responses of the 256 most recently used URLs are kept. The Docker daemon doesn't send caching headers by
+     * itself, this is meant for reverse proxies in front of it that do.
+     *
+     * @param responseCache Whether to cache responses.
+     */
+    public fun responseCache(responseCache: Boolean = true): DockerClientConfigBuilder {
+        this.responseCache = responseCache
+        return this
+    }
+
     /**
      * Configures to use a Unix socket defaults common to the standard Docker configuration.
      *
@@ -151,7 +626,34 @@ public class DockerClientConfigBuilder {
     /**
      * Builds this class to a [DockerClientConfig].
      */
-    public fun build(): DockerClientConfig = DockerClientConfig(socketPath, apiVersion, debugHttpCalls)
+    public fun build(): DockerClientConfig =
+        DockerClientConfig(
+            socketPath = socketPath,
+            apiVersion = apiVersion,
+            debugHttpCalls = debugHttpCalls,
+            httpFixtures = httpFixtures,
+            faultInjection = faultInjection,
+            dryRun = dryRun,
+            tls = tls,
+            tracePropagation = tracePropagation,
+            circuitBreaker = circuitBreaker,
+            requestIdHeader = requestIdHeader,
+            socketOptions = socketOptions,
+            trafficDump = trafficDump,
+            warningHandler = warningHandler,
+            timeouts = timeouts,
+            proxy = if (proxyFromEnvironment) environmentProxyFor(socketPath, tls = tls != null) else proxy,
+            decompressResponses = decompressResponses,
+            responseListener = responseListener,
+            forceHttp1 = forceHttp1,
+            retryPolicy = retryPolicy,
+            userAgent = userAgent,
+            defaultHeaders = defaultHeaders,
+            callTimings = callTimings,
+            redirectPolicy = redirectPolicy,
+            cookies = cookies,
+            responseCache = responseCache,
+        )
 
     /**
      * Returns the value for the given environment variable [key] or [fallback] if it isn't set.
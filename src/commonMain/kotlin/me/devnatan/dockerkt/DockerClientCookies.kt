@@ -0,0 +1,72 @@
+package me.devnatan.dockerkt
+
+import io.ktor.client.plugins.cookies.AcceptAllCookiesStorage
+import io.ktor.client.plugins.cookies.CookiesStorage
+import io.ktor.http.Cookie
+import io.ktor.http.Url
+import io.ktor.util.date.GMTDate
+import kotlinx.coroutines.sync.Mutex
+import kotlinx.coroutines.sync.withLock
+
+/**
+ * Returns the cookies set on this client by the Docker daemon, or a reverse proxy in front of it, that have not
+ * expired yet. Always empty if cookies are not enabled in the client configuration.
+ */
+public suspend fun DockerClient.cookies(): List<Cookie> = cookieJar?.cookies().orEmpty()
+
+/**
+ * Removes all cookies set on this client, e.g. to end the session of an authenticating reverse proxy.
+ */
+public suspend fun DockerClient.clearCookies() {
+    cookieJar?.clear()
+}
+
+/**
+ * Cookie storage of a client that, unlike the Ktor ones, can be inspected and cleared.
+ */
+internal class CookieJar : CookiesStorage {
+    private val mutex = Mutex()
+    private var storage = AcceptAllCookiesStorage()
+    private val cookies = mutableListOf<Cookie>()
+
+    override suspend fun get(requestUrl: Url): List<Cookie> = mutex.withLock { storage }.get(requestUrl)
+
+    override suspend fun addCookie(
+        requestUrl: Url,
+        cookie: Cookie,
+    ) {
+        mutex.withLock {
+            storage.addCookie(requestUrl, cookie)
+
+            // Max-Age takes precedence over Expires, and is relative to now so it's kept as the point in time it ends at
+            val now = GMTDate()
+            val expires = cookie.maxAge?.let { maxAge -> GMTDate(now.timestamp + maxAge * 1000L) } ?: cookie.expires
+
+            // same defaults and identity as the storage, a cookie replaces the one with the same name, domain and path
+            val stored =
+                cookie.copy(domain = cookie.domain ?: requestUrl.host, path = cookie.path ?: "/", maxAge = null, expires = expires)
+            cookies.removeAll { other -> other.name == stored.name && other.domain == stored.domain && other.path == stored.path }
+
+            // an expired cookie, e.g. with Max-Age=0, only removes the stored one
+            if (expires == null || expires > now) cookies.add(stored)
+        }
+    }
+
+    suspend fun cookies(): List<Cookie> =
+        mutex.withLock {
+            val now = GMTDate()
+            cookies.removeAll { cookie -> cookie.expires?.let { expires -> expires <= now } ?: false }
+            cookies.toList()
+        }
+
+    suspend fun clear() =
+        mutex.withLock {
+            storage.close()
+            storage = AcceptAllCookiesStorage()
+            cookies.clear()
+        }
+
+    override fun close() {
+        storage.close()
+    }
+}
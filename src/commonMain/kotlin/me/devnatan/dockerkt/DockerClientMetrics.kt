@@ -0,0 +1,258 @@
+package me.devnatan.dockerkt
+
+import io.ktor.client.call.wrapWithContent
+import io.ktor.client.plugins.api.Send
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.http.content.OutgoingContent
+import io.ktor.http.isWebsocket
+import io.ktor.utils.io.InternalAPI
+import io.ktor.utils.io.readAvailable
+import io.ktor.utils.io.writeFully
+import io.ktor.utils.io.writer
+import kotlinx.serialization.Serializable
+import me.devnatan.dockerkt.io.RequestIdKey
+import me.devnatan.dockerkt.util.DockerKotlinJson
+import kotlin.concurrent.atomics.AtomicLong
+import kotlin.concurrent.atomics.AtomicReference
+import kotlin.concurrent.atomics.ExperimentalAtomicApi
+import kotlin.concurrent.atomics.decrementAndFetch
+import kotlin.concurrent.atomics.fetchAndIncrement
+import kotlin.concurrent.atomics.incrementAndFetch
+import kotlin.time.TimeSource
+
+/**
+ * Upper bounds, in milliseconds, of the request latency histogram buckets.
+ */
+private val LatencyBucketBoundsMillis = listOf(5L, 10L, 25L, 50L, 100L, 250L, 500L, 1000L, 2500L, 5000L, 10000L)
+
+/**
+ * Point-in-time copy of the HTTP metrics of a [DockerClient], meant to be exported to a metrics system like
+ * Micrometer or Prometheus.
+ *
+ * The latency of streaming calls, like following container logs, is only measured until their response headers are
+ * received, their bodies are tracked by [activeStreams] and [bytesReceived] instead.
+ *
+ * @property requests Total amount of requests sent.
+ * @property requestsByStatus Amount of responses received by their status code.
+ * @property failures Amount of requests that didn't get a response at all, e.g. due to a connection error.
+ * @property inFlightRequests Amount of requests currently waiting for a response.
+ * @property bytesSent Total size of the request bodies, for the ones whose size is known upfront.
+ * @property bytesReceived Total amount of response body bytes received, including the ones of responses still being
+ *                         streamed. WebSocket sessions are not counted.
+ * @property latency Distribution of the time taken to receive a response or fail.
+ * @property activeStreams Amount of responses whose body is still being received, e.g. followed logs or events.
+ * @property dnsLookups Amount of hostname lookups made to connect to the Docker daemon. Only measured on the JVM.
+ * @property dnsLookupMicros Total time spent on hostname lookups, in microseconds. Only measured on the JVM.
+ * @property connectionsAcquired Amount of connections requests were sent on, new or pooled. Only measured on the JVM.
+ * @property connectionsReused Amount of [connectionsAcquired] that were taken from the connection pool instead of
+ *                             being established. Only measured on the JVM.
+ */
+@Serializable
+public data class DockerClientMetrics(
+    val requests: Long,
+    val requestsByStatus: Map<Int, Long>,
+    val failures: Long,
+    val inFlightRequests: Long,
+    val bytesSent: Long,
+    val bytesReceived: Long,
+    val latency: LatencyHistogram,
+    val activeStreams: Long = 0,
+    val dnsLookups: Long = 0,
+    val dnsLookupMicros: Long = 0,
+    val connectionsAcquired: Long = 0,
+    val connectionsReused: Long = 0,
+) {
+    /**
+     * Share of the [connectionsAcquired] that were reused from the connection pool, from `0.0` to `1.0`, or `0.0` if
+     * no connection was acquired yet. Only measured on the JVM.
+     */
+    val connectionReuseRate: Double
+        get() = if (connectionsAcquired == 0L) 0.0 else connectionsReused.toDouble() / connectionsAcquired
+
+    /**
+     * Returns the JSON representation of these metrics.
+     */
+    public fun toJson(): String = DockerKotlinJson.encodeToString(this)
+}
+
+/**
+ * Histogram of request latencies.
+ *
+ * @property bucketBoundsMillis Inclusive upper bound of each bucket, in milliseconds.
+ * @property bucketCounts Amount of requests in each bucket, not cumulative. Has one more element than
+ *                        [bucketBoundsMillis], for requests slower than the last bound.
+ * @property count Total amount of measured requests.
+ * @property sumMillis Sum of the latencies of all measured requests, in milliseconds.
+ * @property exemplarRequestIds Id of the last request measured in each bucket, if request ids are enabled in the
+ *                              client configuration, to find a request of each latency range in the logs.
+ */
+@Serializable
+public data class LatencyHistogram(
+    val bucketBoundsMillis: List<Long>,
+    val bucketCounts: List<Long>,
+    val count: Long,
+    val sumMillis: Long,
+    val exemplarRequestIds: List<String?> = emptyList(),
+)
+
+/**
+ * Returns a snapshot of the HTTP metrics collected by this client since it was created.
+ */
+public fun DockerClient.metrics(): DockerClientMetrics = metricsRecorder.snapshot()
+
+@OptIn(ExperimentalAtomicApi::class)
+internal class MetricsRecorder {
+    private val requests = AtomicLong(0)
+    private val requestsByStatus = AtomicReference(emptyMap<Int, Long>())
+    private val failures = AtomicLong(0)
+    private val inFlightRequests = AtomicLong(0)
+    private val bytesSent = AtomicLong(0)
+    private val bytesReceived = AtomicLong(0)
+    private val latencyBuckets = List(LatencyBucketBoundsMillis.size + 1) { AtomicLong(0) }
+    private val latencySumMillis = AtomicLong(0)
+    private val latencyExemplars = List(LatencyBucketBoundsMillis.size + 1) { AtomicReference<String?>(null) }
+    private val activeStreams = AtomicLong(0)
+    private val dnsLookups = AtomicLong(0)
+    private val dnsLookupMicros = AtomicLong(0)
+    private val connectionsAcquired = AtomicLong(0)
+    private val connectionsReused = AtomicLong(0)
+
+    fun started(requestBytes: Long?) {
+        requests.incrementAndFetch()
+        inFlightRequests.incrementAndFetch()
+        requestBytes?.let(bytesSent::addAndFetch)
+    }
+
+    fun completed(
+        status: Int,
+        latencyMillis: Long,
+        requestId: String?,
+    ) {
+        while (true) {
+            val current = requestsByStatus.load()
+            val updated = current + (status to (current[status] ?: 0) + 1)
+            if (requestsByStatus.compareAndSet(current, updated)) break
+        }
+        finished(latencyMillis, requestId)
+    }
+
+    fun streamOpened() {
+        activeStreams.incrementAndFetch()
+    }
+
+    fun received(bytes: Int) {
+        bytesReceived.addAndFetch(bytes.toLong())
+    }
+
+    fun streamClosed() {
+        activeStreams.decrementAndFetch()
+    }
+
+    fun dnsResolved(micros: Long) {
+        dnsLookups.incrementAndFetch()
+        dnsLookupMicros.addAndFetch(micros)
+    }
+
+    fun connectionAcquired(reused: Boolean) {
+        connectionsAcquired.incrementAndFetch()
+        if (reused) connectionsReused.incrementAndFetch()
+    }
+
+    fun failed(
+        latencyMillis: Long,
+        requestId: String?,
+    ) {
+        failures.incrementAndFetch()
+        finished(latencyMillis, requestId)
+    }
+
+    private fun finished(
+        latencyMillis: Long,
+        requestId: String?,
+    ) {
+        inFlightRequests.decrementAndFetch()
+        latencySumMillis.addAndFetch(latencyMillis)
+
+        val bucket =
+            LatencyBucketBoundsMillis
+                .indexOfFirst { bound -> latencyMillis <= bound }
+                .takeUnless { index -> index == -1 } ?: LatencyBucketBoundsMillis.size
+        latencyBuckets[bucket].fetchAndIncrement()
+        requestId?.let(latencyExemplars[bucket]::store)
+    }
+
+    fun snapshot(): DockerClientMetrics {
+        val bucketCounts = latencyBuckets.map(AtomicLong::load)
+        return DockerClientMetrics(
+            requests = requests.load(),
+            requestsByStatus = requestsByStatus.load(),
+            failures = failures.load(),
+            inFlightRequests = inFlightRequests.load(),
+            bytesSent = bytesSent.load(),
+            bytesReceived = bytesReceived.load(),
+            latency =
+                LatencyHistogram(
+                    bucketBoundsMillis = LatencyBucketBoundsMillis,
+                    bucketCounts = bucketCounts,
+                    count = bucketCounts.sum(),
+                    sumMillis = latencySumMillis.load(),
+                    exemplarRequestIds = latencyExemplars.map(AtomicReference<String?>::load),
+                ),
+            activeStreams = activeStreams.load(),
+            dnsLookups = dnsLookups.load(),
+            dnsLookupMicros = dnsLookupMicros.load(),
+            connectionsAcquired = connectionsAcquired.load(),
+            connectionsReused = connectionsReused.load(),
+        )
+    }
+}
+
+@OptIn(InternalAPI::class)
+internal fun createMetricsPlugin(recorder: MetricsRecorder) =
+    createClientPlugin("Metrics") {
+        on(Send) { request ->
+            recorder.started((request.body as? OutgoingContent)?.contentLength)
+            val requestId = request.attributes.getOrNull(RequestIdKey)
+
+            val start = TimeSource.Monotonic.markNow()
+            val call =
+                try {
+                    proceed(request)
+                } catch (e: Throwable) {
+                    recorder.failed(start.elapsedNow().inWholeMilliseconds, requestId)
+                    throw e
+                }
+
+            recorder.completed(
+                status = call.response.status.value,
+                latencyMillis = start.elapsedNow().inWholeMilliseconds,
+                requestId = requestId,
+            )
+
+            // the connection is taken over by the WebSocket session once upgraded
+            if (call.request.url.protocol.isWebsocket()) return@on call
+
+            // counts the body as it's read instead of trusting Content-Length, which streamed responses don't have
+            recorder.streamOpened()
+            val original = call.response.rawContent
+            val counted =
+                client
+                    .writer {
+                        val buffer = ByteArray(8192)
+                        try {
+                            while (true) {
+                                val read = original.readAvailable(buffer, 0, buffer.size)
+                                if (read == -1) break
+
+                                channel.writeFully(buffer, 0, read)
+                                channel.flush()
+                                recorder.received(read)
+                            }
+                        } finally {
+                            recorder.streamClosed()
+                        }
+                    }.channel
+
+            call.wrapWithContent(counted)
+        }
+    }
@@ -0,0 +1,63 @@
+package me.devnatan.dockerkt
+
+import kotlinx.coroutines.CoroutineScope
+import kotlinx.coroutines.TimeoutCancellationException
+import kotlinx.coroutines.currentCoroutineContext
+import kotlinx.coroutines.withContext
+import kotlinx.coroutines.withTimeout
+import kotlin.coroutines.AbstractCoroutineContextElement
+import kotlin.coroutines.CoroutineContext
+import kotlin.time.TimeMark
+
+/**
+ * Absolute deadline of the Docker API calls made in a coroutine, set through [withDeadline].
+ *
+ * @property deadline Point in time at which calls must have completed.
+ */
+public class DockerDeadline internal constructor(
+    public val deadline: TimeMark,
+) : AbstractCoroutineContextElement(DockerDeadline) {
+    public companion object Key : CoroutineContext.Key<DockerDeadline>
+}
+
+/**
+ * Thrown when Docker API calls didn't complete before the deadline set through [withDeadline].
+ */
+public class DockerDeadlineExceededException internal constructor(
+    cause: Throwable?,
+) : DockerException(cause) {
+    override val message: String get() = "Deadline exceeded"
+}
+
+/**
+ * Runs [block] with an absolute [deadline] applied to every Docker API call made in it, including name resolution,
+ * connection, reading the response body and any retry or redirect, so that a caller-level time budget is enforced as a
+ * whole instead of per call.
+ *
+ * Nested deadlines can only make the budget shorter, the earliest deadline always wins.
+ *
+ * @param deadline Point in time at which calls must have completed, usually `TimeSource.Monotonic.markNow() + budget`.
+ * @throws DockerDeadlineExceededException If the deadline is reached before [block] completes.
+ */
+public suspend fun <T> withDeadline(
+    deadline: TimeMark,
+    block: suspend CoroutineScope.() -> T,
+): T {
+    val current = currentCoroutineContext()[DockerDeadline]
+    val effective =
+        if (current != null && current.deadline.elapsedNow() > deadline.elapsedNow()) {
+            current.deadline
+        } else {
+            deadline
+        }
+
+    return try {
+        withContext(DockerDeadline(effective)) {
+            withTimeout(-effective.elapsedNow(), block)
+        }
+    } catch (e: TimeoutCancellationException) {
+        // could have been thrown by a timeout set by the block itself
+        if (!effective.hasPassedNow()) throw e
+        throw DockerDeadlineExceededException(e)
+    }
+}
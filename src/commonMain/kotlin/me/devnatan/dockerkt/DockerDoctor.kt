@@ -0,0 +1,329 @@
+package me.devnatan.dockerkt
+
+import io.ktor.http.Url
+import kotlinx.io.files.Path
+import kotlinx.serialization.Serializable
+import me.devnatan.dockerkt.io.DockerEndpoint
+import me.devnatan.dockerkt.io.FileSystemUtils
+import kotlin.time.Duration
+
+/**
+ * Result of a single check performed by [doctor].
+ *
+ * @property name Human-readable name of the check.
+ * @property status Outcome of the check.
+ * @property message Details about the outcome, e.g. the error that made the check fail.
+ */
+@Serializable
+public data class DoctorCheck(
+    val name: String,
+    val status: Status,
+    val message: String,
+) {
+    @Serializable
+    public enum class Status {
+        Passed,
+        Failed,
+        Skipped,
+    }
+}
+
+/**
+ * Diagnostic report generated by [doctor].
+ *
+ * @property socketPath Docker socket the client is configured to connect to.
+ * @property apiVersion Docker Remote API version the client is configured to use.
+ * @property checks All checks that were performed, in execution order.
+ */
+@Serializable
+public data class DoctorReport(
+    val socketPath: String,
+    val apiVersion: String,
+    val checks: List<DoctorCheck>,
+) {
+    /**
+     * Whether none of the checks failed.
+     */
+    val healthy: Boolean get() = checks.none { check -> check.status == DoctorCheck.Status.Failed }
+}
+
+/**
+ * Checks whether the current user can read from and write to the file at [path].
+ *
+ * @return `null` if it cannot be determined on the current platform.
+ */
+internal expect fun isReadableAndWritable(path: String): Boolean?
+
+/**
+ * Resolves all addresses of the given [host].
+ *
+ * @return `null` if name resolution is not available on the current platform.
+ */
+internal expect suspend fun resolveHostAddresses(host: String): List<String>?
+
+/**
+ * Outcome of a TLS handshake performed by [doctor].
+ */
+internal sealed class TlsHandshakeResult {
+    /**
+     * The handshake completed.
+     *
+     * @property protocol Negotiated TLS version, e.g. `TLSv1.3`.
+     * @property cipherSuite Negotiated cipher suite.
+     * @property peerCertificate Subject of the certificate presented by the Docker daemon.
+     */
+    class Established(
+        val protocol: String,
+        val cipherSuite: String,
+        val peerCertificate: String?,
+    ) : TlsHandshakeResult()
+
+    /**
+     * The handshake failed before the daemon certificate was checked, e.g. because no TLS version or cipher suite
+     * could be agreed on.
+     */
+    class NegotiationFailed(
+        val message: String,
+    ) : TlsHandshakeResult()
+
+    /**
+     * The certificate presented by the Docker daemon was rejected, e.g. because it is not trusted, was issued for
+     * another hostname or doesn't match the pinned public keys.
+     */
+    class CertificateRejected(
+        val message: String,
+    ) : TlsHandshakeResult()
+}
+
+/**
+ * Performs a TLS handshake with the Docker daemon at [host] and [port], then closes the connection.
+ *
+ * @return `null` if TLS is not available on the current platform.
+ */
+internal expect suspend fun performTlsHandshake(
+    host: String,
+    port: Int,
+    tls: DockerTlsConfig,
+    connectTimeout: Duration,
+): TlsHandshakeResult?
+
+/**
+ * Runs a series of checks against the configured Docker daemon connection, from the socket up to the API
+ * version compatibility. Checks that depend on a failed one are reported as skipped.
+ *
+ * Connection failures are reported as failed checks instead of being thrown, so the report can be attached
+ * as is to "can't connect to Docker" support issues.
+ */
+public suspend fun DockerClient.doctor(): DoctorReport {
+    val checks = mutableListOf<DoctorCheck>()
+    val socketPath = config.socketPath
+
+    val transportCheck =
+        when (val endpoint = runCatching { DockerEndpoint.parse(socketPath) }.getOrNull()) {
+            is DockerEndpoint.Unix -> checkUnixSocket(endpoint.path)
+            is DockerEndpoint.NamedPipe ->
+                DoctorCheck("Named pipe", DoctorCheck.Status.Skipped, "Named pipe is verified by the ping check")
+            else -> checkHostResolution(socketPath)
+        }
+    checks.add(transportCheck)
+    checks.addAll(checkTlsHandshake(socketPath, transportCheck))
+
+    if (transportCheck.status == DoctorCheck.Status.Failed) {
+        checks.add(DoctorCheck("Ping", DoctorCheck.Status.Skipped, "Docker daemon is not reachable"))
+        checks.add(DoctorCheck("API version", DoctorCheck.Status.Skipped, "Docker daemon is not reachable"))
+        return DoctorReport(socketPath, config.apiVersion, checks)
+    }
+
+    val pingCheck =
+        runCatching { system.ping() }.fold(
+            onSuccess = { ping ->
+                DoctorCheck("Ping", DoctorCheck.Status.Passed, "Docker daemon supports API up to ${ping.apiVersion}")
+            },
+            onFailure = { error -> DoctorCheck("Ping", DoctorCheck.Status.Failed, describe(error)) },
+        )
+    checks.add(pingCheck)
+
+    if (pingCheck.status == DoctorCheck.Status.Failed) {
+        checks.add(DoctorCheck("API version", DoctorCheck.Status.Skipped, "Docker daemon did not answer the ping"))
+    } else {
+        checks.add(checkApiVersion(config.apiVersion))
+    }
+
+    return DoctorReport(socketPath, config.apiVersion, checks)
+}
+
+private fun checkUnixSocket(path: String): DoctorCheck {
+    val name = "Unix socket"
+    val socketFile = Path(path)
+    if (!FileSystemUtils.exists(socketFile)) {
+        return DoctorCheck(name, DoctorCheck.Status.Failed, "$path does not exist, is the Docker daemon running?")
+    }
+
+    if (FileSystemUtils.isDirectory(socketFile)) {
+        return DoctorCheck(name, DoctorCheck.Status.Failed, "$path is a directory, not a socket")
+    }
+
+    return when (isReadableAndWritable(path)) {
+        true -> DoctorCheck(name, DoctorCheck.Status.Passed, "$path exists and is accessible")
+        false -> DoctorCheck(name, DoctorCheck.Status.Failed, "Permission denied on $path")
+        null -> DoctorCheck(name, DoctorCheck.Status.Passed, "$path exists, permissions could not be verified")
+    }
+}
+
+private suspend fun checkHostResolution(socketPath: String): DoctorCheck {
+    val name = "Host resolution"
+    val host =
+        runCatching { Url(socketPath).host }.getOrNull()
+            ?: return DoctorCheck(name, DoctorCheck.Status.Failed, "Invalid Docker host: $socketPath")
+
+    return runCatching { resolveHostAddresses(host) }.fold(
+        onSuccess = { addresses ->
+            when {
+                addresses == null -> {
+                    DoctorCheck(name, DoctorCheck.Status.Skipped, "Name resolution is not available on this platform")
+                }
+
+                addresses.isEmpty() -> {
+                    DoctorCheck(name, DoctorCheck.Status.Failed, "$host did not resolve to any address")
+                }
+
+                else -> {
+                    DoctorCheck(name, DoctorCheck.Status.Passed, "$host resolved to ${addresses.joinToString()}")
+                }
+            }
+        },
+        onFailure = { error -> DoctorCheck(name, DoctorCheck.Status.Failed, describe(error)) },
+    )
+}
+
+private suspend fun DockerClient.checkTlsHandshake(
+    socketPath: String,
+    transportCheck: DoctorCheck,
+): List<DoctorCheck> {
+    val handshake = "TLS handshake"
+    val certificate = "TLS certificate"
+    val tls = config.tls
+    val endpoint = runCatching { DockerEndpoint.parse(socketPath) }.getOrNull()
+    if (tls == null || endpoint !is DockerEndpoint.Tcp || transportCheck.status == DoctorCheck.Status.Failed) {
+        val reason =
+            when {
+                tls == null -> "TLS is not enabled for this client"
+                endpoint !is DockerEndpoint.Tcp -> "TLS is only used to connect over TCP"
+                else -> "Docker daemon is not reachable"
+            }
+        return listOf(
+            DoctorCheck(handshake, DoctorCheck.Status.Skipped, reason),
+            DoctorCheck(certificate, DoctorCheck.Status.Skipped, reason),
+        )
+    }
+
+    val result =
+        runCatching { performTlsHandshake(endpoint.host, endpoint.port, tls, config.timeouts.connect) }
+            .getOrElse { error ->
+                return listOf(
+                    DoctorCheck(handshake, DoctorCheck.Status.Failed, describe(error)),
+                    DoctorCheck(certificate, DoctorCheck.Status.Skipped, "TLS connection could not be established"),
+                )
+            }
+
+    return when (result) {
+        null -> {
+            listOf(
+                DoctorCheck(handshake, DoctorCheck.Status.Skipped, "TLS is not available on this platform"),
+                DoctorCheck(certificate, DoctorCheck.Status.Skipped, "TLS is not available on this platform"),
+            )
+        }
+
+        is TlsHandshakeResult.Established -> {
+            val subject = result.peerCertificate?.let { subject -> " $subject" }.orEmpty()
+            val verification = if (tls.acceptInvalidCertificates) "was accepted without verification" else "was verified"
+            listOf(
+                DoctorCheck(
+                    handshake,
+                    DoctorCheck.Status.Passed,
+                    "Negotiated ${result.protocol} with ${result.cipherSuite}",
+                ),
+                DoctorCheck(
+                    certificate,
+                    DoctorCheck.Status.Passed,
+                    "Docker daemon certificate$subject $verification",
+                ),
+            )
+        }
+
+        is TlsHandshakeResult.NegotiationFailed -> {
+            listOf(
+                DoctorCheck(
+                    handshake,
+                    DoctorCheck.Status.Failed,
+                    "Could not negotiate TLS with the Docker daemon: ${result.message}",
+                ),
+                DoctorCheck(certificate, DoctorCheck.Status.Skipped, "TLS handshake failed"),
+            )
+        }
+
+        is TlsHandshakeResult.CertificateRejected -> {
+            listOf(
+                DoctorCheck(handshake, DoctorCheck.Status.Skipped, "TLS handshake was aborted by the certificate check"),
+                DoctorCheck(certificate, DoctorCheck.Status.Failed, result.message),
+            )
+        }
+    }
+}
+
+private suspend fun DockerClient.checkApiVersion(apiVersion: String): DoctorCheck {
+    val name = "API version"
+    val version =
+        runCatching { system.version() }.getOrElse { error ->
+            return DoctorCheck(name, DoctorCheck.Status.Failed, describe(error))
+        }
+
+    return when {
+        compareApiVersions(apiVersion, version.minApiVersion) < 0 -> {
+            DoctorCheck(
+                name,
+                DoctorCheck.Status.Failed,
+                "API version $apiVersion is older than the minimum supported by the daemon (${version.minApiVersion})",
+            )
+        }
+
+        compareApiVersions(apiVersion, version.apiVersion) > 0 -> {
+            DoctorCheck(
+                name,
+                DoctorCheck.Status.Failed,
+                "API version $apiVersion is newer than the maximum supported by the daemon (${version.apiVersion})",
+            )
+        }
+
+        else -> {
+            DoctorCheck(
+                name,
+                DoctorCheck.Status.Passed,
+                "API version $apiVersion is supported by Docker ${version.version} " +
+                    "(${version.minApiVersion} - ${version.apiVersion})",
+            )
+        }
+    }
+}
+
+/**
+ * Compares two Docker Remote API versions, e.g. `1.41` and `1.9`, component by component.
+ */
+internal fun compareApiVersions(
+    a: String,
+    b: String,
+): Int {
+    val left = a.split(".").map { it.toIntOrNull() ?: 0 }
+    val right = b.split(".").map { it.toIntOrNull() ?: 0 }
+    for (index in 0 until maxOf(left.size, right.size)) {
+        val result = left.getOrElse(index) { 0 }.compareTo(right.getOrElse(index) { 0 })
+        if (result != 0) return result
+    }
+    return 0
+}
+
+private fun describe(error: Throwable): String =
+    generateSequence(error) { it.cause }
+        .mapNotNull { it.message }
+        .firstOrNull()
+        ?: error::class.simpleName.orEmpty()
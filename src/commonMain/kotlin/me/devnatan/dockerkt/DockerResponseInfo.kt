@@ -0,0 +1,189 @@
+@file:OptIn(ExperimentalTime::class)
+
+package me.devnatan.dockerkt
+
+import io.ktor.client.plugins.api.Send
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.util.AttributeKey
+import me.devnatan.dockerkt.io.NotModifiedKey
+import me.devnatan.dockerkt.io.RedirectHistoryKey
+import me.devnatan.dockerkt.io.RedirectPolicy
+import me.devnatan.dockerkt.io.RequestIdKey
+import me.devnatan.dockerkt.io.originalContentEncoding
+import kotlin.concurrent.Volatile
+import kotlin.time.Duration
+import kotlin.time.ExperimentalTime
+import kotlin.time.Instant
+import kotlin.time.TimeMark
+import kotlin.time.TimeSource
+
+// read by the engine from the request data, so that nothing is added to the request itself
+internal val EngineCallInfoKey = AttributeKey<EngineCallInfo>("EngineCallInfo")
+private val CallStartKey = AttributeKey<TimeMark>("CallStart")
+
+/**
+ * Details about how a call to the Docker daemon was answered, reported to the response listener set in the client
+ * configuration.
+ *
+ * @property method HTTP method of the call.
+ * @property path Path of the call.
+ * @property statusCode Status code of the response.
+ * @property protocolVersion HTTP version negotiated for the call, e.g. `HTTP/1.1` or `HTTP/2.0`.
+ * @property headers Headers of the response, with all values of the headers that were sent more than once.
+ * @property reasonPhrase Reason phrase of the status line sent by the Docker daemon, e.g. `Conflict`.
+ * @property timings Time spent on each phase of the call, if call timings are enabled in the client configuration.
+ * @property remoteAddress Address of the Docker daemon the call was sent to, as resolved, e.g. to tell apart daemons
+ *                         behind a round-robin DNS name. Only reported on the JVM.
+ * @property localAddress Local address of the connection the call was sent through. Only reported on the JVM.
+ * @property connectionReused Whether the call was sent through a pooled connection instead of a new one, to verify
+ *                            that keep-alive works. Only reported on the JVM.
+ * @property peerCertificate Certificate presented by the Docker daemon on TLS connections. Only reported on the JVM.
+ * @property redirects URLs the call was redirected from, in the order they were visited. See [RedirectPolicy].
+ * @property fromCache Whether the Docker daemon answered with `304 Not Modified` and the response was served from the
+ *                     response cache of the client.
+ * @property requestId The id the request was sent with, if request ids are enabled in the client configuration, to
+ *                     correlate the call with the logs of the Docker daemon or of a reverse proxy in front of it.
+ * @property contentEncoding The `Content-Encoding` the response body was sent with, if any. Gzip and deflate bodies
+ *                           are decoded before being read when response decompression is enabled in the client
+ *                           configuration, zstd bodies are never decoded.
+ */
+public data class DockerResponseInfo(
+    val method: String,
+    val path: String,
+    val statusCode: Int,
+    val protocolVersion: String,
+    val headers: Map<String, List<String>> = emptyMap(),
+    val reasonPhrase: String = "",
+    val timings: DockerCallTimings? = null,
+    val remoteAddress: String? = null,
+    val localAddress: String? = null,
+    val connectionReused: Boolean? = null,
+    val peerCertificate: DockerPeerCertificate? = null,
+    val redirects: List<String> = emptyList(),
+    val fromCache: Boolean = false,
+    val requestId: String? = null,
+    val contentEncoding: String? = null,
+) {
+    /**
+     * Returns all values of the header with the given [name], matched ignoring case, in the order they were received.
+     */
+    public fun headerValues(name: String): List<String> =
+        headers.entries
+            .filter { (header, _) -> header.equals(name, ignoreCase = true) }
+            .flatMap { (_, values) -> values }
+}
+
+/**
+ * Time spent on each phase of a call, to tell whether slowness comes from name resolution, the connection, the TLS
+ * handshake or the Docker daemon itself.
+ *
+ * Phases that were not gone through, e.g. because a pooled connection was reused, or that can't be measured on the
+ * current platform are `null`. Only the JVM measures the phases below [total].
+ *
+ * @property dns Time spent resolving the host name.
+ * @property connect Time spent establishing the connection, including the TLS handshake.
+ * @property tls Time spent on the TLS handshake.
+ * @property timeToFirstByte Time between the request being fully sent and the response starting to be received.
+ * @property total Time between the call being started and the response headers being received.
+ */
+public data class DockerCallTimings(
+    val dns: Duration? = null,
+    val connect: Duration? = null,
+    val tls: Duration? = null,
+    val timeToFirstByte: Duration? = null,
+    val total: Duration,
+)
+
+/**
+ * Certificate presented by the Docker daemon on a TLS connection, e.g. to warn about its imminent expiry.
+ *
+ * @property subject Distinguished name of the certificate subject.
+ * @property issuer Distinguished name of the certificate issuer.
+ * @property subjectAlternativeNames DNS names and IP addresses the certificate is valid for.
+ * @property notBefore Point in time the certificate is valid from.
+ * @property notAfter Point in time the certificate expires at.
+ */
+public data class DockerPeerCertificate(
+    val subject: String,
+    val issuer: String,
+    val subjectAlternativeNames: List<String>,
+    val notBefore: Instant,
+    val notAfter: Instant,
+)
+
+/**
+ * Details about a call only known by the engine, filled in by it while executing the call since Ktor doesn't expose
+ * them. Handed over to the engine in the attributes of the request, under [EngineCallInfoKey].
+ */
+internal class EngineCallInfo {
+    @Volatile var dns: Duration? = null
+
+    @Volatile var connect: Duration? = null
+
+    @Volatile var tls: Duration? = null
+
+    @Volatile var timeToFirstByte: Duration? = null
+
+    @Volatile var remoteAddress: String? = null
+
+    @Volatile var localAddress: String? = null
+
+    @Volatile var connectionReused: Boolean? = null
+
+    @Volatile var peerCertificate: DockerPeerCertificate? = null
+}
+
+/**
+ * @param engineReportsCallInfo Whether the engine reports the details of the calls that only it knows.
+ */
+internal fun createResponseListenerPlugin(
+    listener: (DockerResponseInfo) -> Unit,
+    callTimings: Boolean,
+    engineReportsCallInfo: Boolean,
+) = createClientPlugin("ResponseListener") {
+    onRequest { request, _ ->
+        if (callTimings) request.attributes.put(CallStartKey, TimeSource.Monotonic.markNow())
+    }
+
+    if (engineReportsCallInfo) {
+        // each attempt gets its own details, so that a retry doesn't report the connection of a failed attempt
+        on(Send) { request ->
+            request.attributes.put(EngineCallInfoKey, EngineCallInfo())
+            proceed(request)
+        }
+    }
+
+    onResponse { response ->
+        val engineInfo = response.call.attributes.getOrNull(EngineCallInfoKey)
+        val timings =
+            response.call.attributes.getOrNull(CallStartKey)?.let { start ->
+                DockerCallTimings(
+                    dns = engineInfo?.dns,
+                    connect = engineInfo?.connect,
+                    tls = engineInfo?.tls,
+                    timeToFirstByte = engineInfo?.timeToFirstByte,
+                    total = start.elapsedNow(),
+                )
+            }
+
+        listener(
+            DockerResponseInfo(
+                method = response.call.request.method.value,
+                path = response.call.request.url.encodedPath,
+                statusCode = response.status.value,
+                protocolVersion = response.version.toString(),
+                headers = response.headers.entries().associate { (name, values) -> name to values },
+                reasonPhrase = response.status.description,
+                timings = timings,
+                remoteAddress = engineInfo?.remoteAddress,
+                localAddress = engineInfo?.localAddress,
+                connectionReused = engineInfo?.connectionReused,
+                peerCertificate = engineInfo?.peerCertificate,
+                redirects = response.call.attributes.getOrNull(RedirectHistoryKey).orEmpty(),
+                fromCache = response.call.attributes.contains(NotModifiedKey),
+                requestId = response.call.attributes.getOrNull(RequestIdKey),
+                contentEncoding = response.originalContentEncoding(),
+            ),
+        )
+    }
+}
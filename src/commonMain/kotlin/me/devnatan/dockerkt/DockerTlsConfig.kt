@@ -0,0 +1,408 @@
+package me.devnatan.dockerkt
+
+import kotlinx.io.files.Path
+import me.devnatan.dockerkt.io.FileSystemUtils
+import kotlin.time.Duration
+
+/**
+ * Name of the CA certificate file in a Docker certificates directory.
+ */
+private const val CaCertificateFileName = "ca.pem"
+
+/**
+ * Name of the client certificate file in a Docker certificates directory.
+ */
+private const val ClientCertificateFileName = "cert.pem"
+
+/**
+ * Name of the client private key file in a Docker certificates directory.
+ */
+private const val ClientKeyFileName = "key.pem"
+
+// "sha256/" followed by the base64 encoded SHA-256 hash, 32 bytes long, of a SubjectPublicKeyInfo
+private val PublicKeyPinRegex = Regex("^sha256/[A-Za-z0-9+/]{43}=$")
+
+/**
+ * TLS configuration used to connect to a Docker daemon exposed over TCP with `--tlsverify`.
+ *
+ * TLS is only supported on the JVM for now.
+ *
+ * @property clientIdentity Certificate and private key the client authenticates itself with, if any.
+ * @property trustStore Where the root certificates used to verify the Docker daemon certificate come from.
+ *                      Ignored if [caCertificatePath] or [caCertificates] is set.
+ * @property caCertificatePath Path to a PEM file with the CA certificates the Docker daemon certificate must be
+ *                             signed by, like the `ca.pem` file generated for `dockerd --tlsverify`.
+ * @property sessionCacheSize Maximum amount of TLS sessions cached for resumption, `0` means unlimited.
+ *                            If not set, the runtime default is used.
+ * @property sessionTimeout How long cached TLS sessions can be resumed for. If not set, the runtime default is used.
+ * @property minVersion Lowest TLS version negotiated with the Docker daemon.
+ * @property maxVersion Highest TLS version negotiated with the Docker daemon.
+ * @property cipherSuites Cipher suites allowed to be negotiated, by their IANA names (e.g.
+ *                        `TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384`), or `null` to allow the runtime defaults.
+ * @property securityProvider Name of the security provider TLS is implemented by, e.g. `BCFIPS` to use a
+ *                            FIPS-validated Bouncy Castle installation, or `null` to use the runtime defaults.
+ *                            Key stores, keys, certificates and public key pin digests are handled by this provider
+ *                            too, so it must implement all of them. The provider must already be registered in the
+ *                            runtime. Certificates of the operating system trust store are still read by the runtime.
+ * @property acceptInvalidCertificates Accepts any Docker daemon certificate, even self-signed or expired ones.
+ *                                     Makes connections vulnerable to man-in-the-middle attacks, only meant for labs.
+ * @property acceptInvalidHostnames Accepts Docker daemon certificates issued for another hostname.
+ *                                  Makes connections vulnerable to man-in-the-middle attacks, only meant for labs.
+ * @property pinnedPublicKeys SHA-256 hashes of the public keys the Docker daemon certificate chain must contain one
+ *                            of, in the `sha256/<base64>` format, e.g. as printed by `openssl x509 -pubkey | openssl
+ *                            pkey -pubin -outform der | openssl dgst -sha256 -binary | base64`. Connections are
+ *                            rejected if none matches, even if the chain is otherwise valid. Only the daemon
+ *                            certificate and the certificates signing it count, also with [acceptInvalidCertificates]
+ *                            so that a self-signed daemon certificate can be pinned. Empty to not pin keys.
+ * @property caCertificates PEM encoded CA certificates the Docker daemon certificate must be signed by, for CAs that
+ *                          are not stored in a file. Ignored if [caCertificatePath] is set.
+ */
+public class DockerTlsConfig(
+    public val clientIdentity: ClientIdentity? = null,
+    public val trustStore: TrustStore = TrustStore.Bundled,
+    public val sessionCacheSize: Int? = null,
+    public val sessionTimeout: Duration? = null,
+    public val caCertificatePath: String? = null,
+    public val minVersion: TlsVersion = TlsVersion.Tls12,
+    public val maxVersion: TlsVersion = TlsVersion.Tls13,
+    public val cipherSuites: List<String>? = null,
+    public val securityProvider: String? = null,
+    public val acceptInvalidCertificates: Boolean = false,
+    public val acceptInvalidHostnames: Boolean = false,
+    public val pinnedPublicKeys: List<String> = emptyList(),
+    public val caCertificates: ByteArray? = null,
+) {
+    init {
+        require(minVersion <= maxVersion) { "Minimum TLS version cannot be higher than maximum TLS version" }
+        require(cipherSuites == null || cipherSuites.isNotEmpty()) { "Cipher suites allowlist cannot be empty" }
+        require(sessionCacheSize == null || sessionCacheSize >= 0) { "Session cache size cannot be negative" }
+        require(sessionTimeout == null || !sessionTimeout.isNegative()) { "Session timeout cannot be negative" }
+        pinnedPublicKeys.forEach { pin ->
+            require(pin.matches(PublicKeyPinRegex)) { "Invalid public key pin, expected sha256/<base64>: $pin" }
+        }
+    }
+
+    /**
+     * Whether TLS runs in FIPS mode, that is, [securityProvider] is registered and reports itself as FIPS-validated
+     * by its name or description, or by the FIPS mode flag of Bouncy Castle JSSE. Always `false` without a
+     * [securityProvider] and on platforms that don't support TLS.
+     */
+    public val isFips: Boolean
+        get() = securityProvider != null && isFipsSecurityProvider(securityProvider)
+}
+
+/**
+ * Checks whether the registered security provider named [name] is FIPS-validated.
+ *
+ * @return `false` if the provider is not registered or security providers are not available on the current platform.
+ */
+internal expect fun isFipsSecurityProvider(name: String): Boolean
+
+/**
+ * TLS protocol versions supported to connect to the Docker daemon.
+ *
+ * @property protocolName Standard name of the protocol version.
+ */
+public enum class TlsVersion(
+    public val protocolName: String,
+) {
+    Tls12("TLSv1.2"),
+    Tls13("TLSv1.3"),
+}
+
+/**
+ * Source of the root certificates used to verify the Docker daemon certificate.
+ */
+public enum class TrustStore {
+    /**
+     * Root certificates bundled with the runtime, e.g. the `cacerts` file of the JVM.
+     */
+    Bundled,
+
+    /**
+     * Root certificates of the operating system: the Windows certificate store, the macOS Keychain or the
+     * CA bundle under `/etc/ssl` on Linux. Required for internal CAs and corporate MITM proxies to work.
+     */
+    System,
+}
+
+/**
+ * Certificate and private key a client authenticates itself with to the Docker daemon.
+ */
+public sealed class ClientIdentity {
+    /**
+     * Identity stored in a PKCS#12 (`.p12` or `.pfx`) bundle, the format most enterprise PKI systems and Windows
+     * tooling export client certificates in.
+     *
+     * @property path Path to the bundle on the local filesystem.
+     * @property password Password the bundle and its private key are protected with.
+     */
+    public class Pkcs12(
+        public val path: String,
+        public val password: CharArray,
+    ) : ClientIdentity() {
+        /**
+         * Overwrites the [password] with zeros so that it doesn't linger in memory.
+         *
+         * The TLS context of a client is created when the client is, so this can be called right after creating it.
+         */
+        public fun wipe() {
+            password.fill(Char.MIN_VALUE)
+        }
+    }
+
+    /**
+     * Identity stored in PEM files, like the `cert.pem` and `key.pem` files used by the Docker CLI.
+     *
+     * @property certificatePath Path to the PEM file with the client certificate chain.
+     * @property privateKeyPath Path to the PEM file with the unencrypted PKCS#1 (RSA) or PKCS#8 private key.
+     */
+    public class Pem(
+        public val certificatePath: String,
+        public val privateKeyPath: String,
+    ) : ClientIdentity()
+
+    /**
+     * Identity held in memory as PEM encoded data, e.g. read from a keychain or a bundled resource, so that it
+     * never has to be written to a temporary file.
+     *
+     * @property certificate PEM encoded client certificate chain.
+     * @property privateKey PEM encoded unencrypted PKCS#1 (RSA) or PKCS#8 private key.
+     */
+    public class PemData(
+        public val certificate: ByteArray,
+        public val privateKey: ByteArray,
+    ) : ClientIdentity() {
+        /**
+         * Overwrites the [privateKey] with zeros so that it doesn't linger in memory.
+         *
+         * The TLS context of a client is created when the client is, so this can be called right after creating it.
+         */
+        public fun wipe() {
+            privateKey.fill(0)
+        }
+    }
+}
+
+/**
+ * Mutable builder for [DockerTlsConfig].
+ */
+public class DockerTlsConfigBuilder {
+    private var clientIdentity: ClientIdentity? = null
+    private var trustStore: TrustStore = TrustStore.Bundled
+    private var sessionCacheSize: Int? = null
+    private var sessionTimeout: Duration? = null
+    private var caCertificatePath: String? = null
+    private var minVersion: TlsVersion = TlsVersion.Tls12
+    private var maxVersion: TlsVersion = TlsVersion.Tls13
+    private var cipherSuites: List<String>? = null
+    private var securityProvider: String? = null
+    private var acceptInvalidCertificates: Boolean = false
+    private var acceptInvalidHostnames: Boolean = false
+    private var pinnedPublicKeys: List<String> = emptyList()
+    private var caCertificates: ByteArray? = null
+
+    /**
+     * Sets the client identity from a PKCS#12 (`.p12` or `.pfx`) bundle.
+     *
+     * @param path Path to the bundle on the local filesystem.
+     * @param password Password the bundle and its private key are protected with.
+     */
+    public fun pkcs12Identity(
+        path: String,
+        password: CharArray,
+    ): DockerTlsConfigBuilder {
+        clientIdentity = ClientIdentity.Pkcs12(path, password)
+        return this
+    }
+
+    /**
+     * Sets the client identity from PEM files.
+     *
+     * @param certificatePath Path to the PEM file with the client certificate chain.
+     * @param privateKeyPath Path to the PEM file with the unencrypted PKCS#1 (RSA) or PKCS#8 private key.
+     */
+    public fun pemIdentity(
+        certificatePath: String,
+        privateKeyPath: String,
+    ): DockerTlsConfigBuilder {
+        clientIdentity = ClientIdentity.Pem(certificatePath, privateKeyPath)
+        return this
+    }
+
+    /**
+     * Sets the client identity from PEM encoded data held in memory.
+     *
+     * @param certificate PEM encoded client certificate chain.
+     * @param privateKey PEM encoded unencrypted PKCS#1 (RSA) or PKCS#8 private key.
+     */
+    public fun pemIdentity(
+        certificate: ByteArray,
+        privateKey: ByteArray,
+    ): DockerTlsConfigBuilder {
+        clientIdentity = ClientIdentity.PemData(certificate, privateKey)
+        return this
+    }
+
+    /**
+     * Sets the PEM file with the CA certificates the Docker daemon certificate must be signed by.
+     *
+     * @param path Path to the PEM file.
+     */
+    public fun caCertificate(path: String?): DockerTlsConfigBuilder {
+        caCertificatePath = path
+        return this
+    }
+
+    /**
+     * Sets the PEM encoded CA certificates the Docker daemon certificate must be signed by, held in memory.
+     *
+     * @param pem PEM encoded CA certificates.
+     */
+    public fun caCertificate(pem: ByteArray): DockerTlsConfigBuilder {
+        caCertificatePath = null
+        caCertificates = pem
+        return this
+    }
+
+    /**
+     * Loads the CA certificate, client certificate and client private key from a directory following the Docker CLI
+     * convention, that is, the `ca.pem`, `cert.pem` and `key.pem` files found in `DOCKER_CERT_PATH`.
+     *
+     * @param directory Path to the certificates directory.
+     * @throws IllegalArgumentException If the directory or any of the files in it are missing.
+     */
+    public fun certPath(directory: String): DockerTlsConfigBuilder {
+        val root = Path(directory)
+        require(FileSystemUtils.isDirectory(root)) { "Docker certificates directory not found: $directory" }
+
+        val (ca, certificate, key) =
+            listOf(CaCertificateFileName, ClientCertificateFileName, ClientKeyFileName).map { fileName ->
+                val file = Path(root, fileName)
+                require(FileSystemUtils.exists(file)) { "Missing $fileName in Docker certificates directory: $directory" }
+                file.toString()
+            }
+
+        caCertificatePath = ca
+        clientIdentity = ClientIdentity.Pem(certificate, key)
+        return this
+    }
+
+    /**
+     * Sets where the root certificates used to verify the Docker daemon certificate come from.
+     *
+     * @param trustStore The trust store.
+     */
+    public fun trustStore(trustStore: TrustStore): DockerTlsConfigBuilder {
+        this.trustStore = trustStore
+        return this
+    }
+
+    /**
+     * Configures the cache of TLS sessions, which are resumed by subsequent connections to the same Docker daemon
+     * instead of doing a full handshake again.
+     *
+     * @param size Maximum amount of cached sessions, `0` means unlimited.
+     * @param timeout How long cached sessions can be resumed for.
+     */
+    public fun sessionCache(
+        size: Int? = null,
+        timeout: Duration? = null,
+    ): DockerTlsConfigBuilder {
+        sessionCacheSize = size
+        sessionTimeout = timeout
+        return this
+    }
+
+    /**
+     * Restricts the TLS versions negotiated with the Docker daemon.
+     *
+     * @param min Lowest allowed TLS version.
+     * @param max Highest allowed TLS version.
+     */
+    public fun versions(
+        min: TlsVersion = TlsVersion.Tls12,
+        max: TlsVersion = TlsVersion.Tls13,
+    ): DockerTlsConfigBuilder {
+        minVersion = min
+        maxVersion = max
+        return this
+    }
+
+    /**
+     * Restricts the cipher suites negotiated with the Docker daemon.
+     *
+     * @param cipherSuites Allowed cipher suites by their IANA names, or `null` to allow the runtime defaults.
+     */
+    public fun cipherSuites(cipherSuites: List<String>?): DockerTlsConfigBuilder {
+        this.cipherSuites = cipherSuites
+        return this
+    }
+
+    /**
+     * Sets the security provider TLS is implemented by, e.g. a FIPS-validated one.
+     *
+     * @param name Name of an already registered security provider, or `null` to use the runtime default.
+     */
+    public fun securityProvider(name: String?): DockerTlsConfigBuilder {
+        securityProvider = name
+        return this
+    }
+
+    /**
+     * Disables the verification of the Docker daemon certificate, so that self-signed or expired ones are accepted.
+     *
+     * **This makes connections vulnerable to man-in-the-middle attacks**, only use it in lab setups.
+     * Prefer trusting the daemon CA with [caCertificate] instead.
+     *
+     * @param accept Whether invalid certificates are accepted.
+     */
+    public fun acceptInvalidCertificates(accept: Boolean = true): DockerTlsConfigBuilder {
+        acceptInvalidCertificates = accept
+        return this
+    }
+
+    /**
+     * Disables the verification of the hostname the Docker daemon certificate was issued for.
+     *
+     * **This makes connections vulnerable to man-in-the-middle attacks**, only use it in lab setups.
+     *
+     * @param accept Whether certificates issued for another hostname are accepted.
+     */
+    public fun acceptInvalidHostnames(accept: Boolean = true): DockerTlsConfigBuilder {
+        acceptInvalidHostnames = accept
+        return this
+    }
+
+    /**
+     * Pins the public keys the Docker daemon certificate chain must contain one of, so that connections are rejected
+     * if the daemon presents another key, even in a certificate signed by a trusted CA. Pins are also enforced when
+     * accepting invalid certificates, e.g. to trust a single self-signed daemon certificate.
+     *
+     * @param pins SHA-256 hashes of the SubjectPublicKeyInfo of the keys, in the `sha256/<base64>` format.
+     */
+    public fun pinPublicKeys(vararg pins: String): DockerTlsConfigBuilder {
+        pinnedPublicKeys = pins.toList()
+        return this
+    }
+
+    /**
+     * Builds this class to a [DockerTlsConfig].
+     */
+    public fun build(): DockerTlsConfig =
+        DockerTlsConfig(
+            clientIdentity = clientIdentity,
+            trustStore = trustStore,
+            sessionCacheSize = sessionCacheSize,
+            sessionTimeout = sessionTimeout,
+            caCertificatePath = caCertificatePath,
+            minVersion = minVersion,
+            maxVersion = maxVersion,
+            cipherSuites = cipherSuites,
+            securityProvider = securityProvider,
+            acceptInvalidCertificates = acceptInvalidCertificates,
+            acceptInvalidHostnames = acceptInvalidHostnames,
+            pinnedPublicKeys = pinnedPublicKeys,
+            caCertificates = caCertificates,
+        )
+}
@@ -0,0 +1,151 @@
+package me.devnatan.dockerkt
+
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.client.statement.bodyAsText
+import io.ktor.http.ContentType
+import io.ktor.http.HttpHeaders
+import io.ktor.http.contentType
+import io.ktor.http.isSuccess
+import kotlinx.coroutines.sync.Mutex
+import kotlinx.coroutines.sync.withLock
+import kotlinx.serialization.json.JsonElement
+import kotlinx.serialization.json.JsonObject
+import me.devnatan.dockerkt.io.RequestIdKey
+import me.devnatan.dockerkt.util.DockerKotlinJson
+
+// warn-code SP warn-agent SP DQUOTE warn-text DQUOTE [ SP warn-date ], see RFC 7234 section 5.5
+private val WarningHeaderRegex = Regex("^\\d{3} \\S+ \"((?:[^\"\\\\]|\\\\.)*)\"")
+
+/**
+ * A warning reported by the Docker daemon on a call, like the use of a deprecated option or field that will be
+ * removed in a future API version.
+ *
+ * @property message The warning message.
+ * @property method HTTP method of the call the warning was reported on.
+ * @property path Path of the call the warning was reported on.
+ * @property requestId The id the request of the call was sent with, if request ids are enabled in the client
+ *                     configuration.
+ */
+public data class DockerWarning(
+    val message: String,
+    val method: String,
+    val path: String,
+    val requestId: String? = null,
+)
+
+/**
+ * Warning handler used by default, which logs the warnings through SLF4J on the JVM, like the Ktor client does, and
+ * to the standard output on other platforms.
+ */
+public val DefaultDockerWarningHandler: (DockerWarning) -> Unit = { warning ->
+    val requestId = warning.requestId?.let { id -> " (request $id)" }.orEmpty()
+    DockerKotlinLogger.warn("Warning from Docker API on ${warning.method} ${warning.path}$requestId: ${warning.message}")
+}
+
+/**
+ * A field the Docker daemon still sends in the responses to [path] that is deprecated, and no longer sent from API
+ * version [removedIn] onwards.
+ */
+internal class DeprecatedField(
+    val path: Regex,
+    val field: List<String>,
+    val removedIn: String,
+)
+
+private val ContainerInspectPath = Regex("^/v[\\d.]+/containers/[^/]+/json$")
+private val ImageInspectPath = Regex("^/v[\\d.]+/images/.+/json$")
+
+// fields that are known to be removed in a future API version, reported before they start failing deserialization
+internal val KnownDeprecatedFields: List<DeprecatedField> =
+    listOf(
+        "Bridge",
+        "EndpointID",
+        "Gateway",
+        "GlobalIPv6Address",
+        "GlobalIPv6PrefixLen",
+        "HairpinMode",
+        "IPAddress",
+        "IPPrefixLen",
+        "IPv6Gateway",
+        "LinkLocalIPv6Address",
+        "LinkLocalIPv6PrefixLen",
+        "MacAddress",
+        "SecondaryIPAddresses",
+        "SecondaryIPv6Addresses",
+    ).map { name -> DeprecatedField(ContainerInspectPath, listOf("NetworkSettings", name), removedIn = "1.52") } +
+        DeprecatedField(ImageInspectPath, listOf("VirtualSize"), removedIn = "1.44") +
+        DeprecatedField(ImageInspectPath, listOf("Container"), removedIn = "1.45") +
+        DeprecatedField(ImageInspectPath, listOf("ContainerConfig"), removedIn = "1.45")
+
+/**
+ * Returns the known deprecated fields that may be sent in the responses to [path] for the API version [apiVersion].
+ */
+internal fun deprecatedFieldsFor(
+    path: String,
+    apiVersion: String,
+): List<DeprecatedField> =
+    KnownDeprecatedFields.filter { deprecated ->
+        compareApiVersions(apiVersion, deprecated.removedIn) < 0 && deprecated.path.matches(path)
+    }
+
+/**
+ * Returns the [candidates] present in the JSON response [body].
+ */
+internal fun findDeprecatedFields(
+    body: String,
+    candidates: List<DeprecatedField>,
+): List<DeprecatedField> {
+    val root = runCatching { DockerKotlinJson.parseToJsonElement(body) }.getOrNull() ?: return emptyList()
+    return candidates.filter { deprecated ->
+        val parent =
+            deprecated.field.dropLast(1).fold<String, JsonElement?>(root) { element, name ->
+                (element as? JsonObject)?.get(name)
+            }
+        (parent as? JsonObject)?.containsKey(deprecated.field.last()) == true
+    }
+}
+
+internal fun parseWarningHeader(value: String): String =
+    WarningHeaderRegex
+        .find(value)
+        ?.groupValues
+        ?.get(1)
+        ?.replace("\\\"", "\"")
+        ?: value
+
+internal fun createWarningsPlugin(
+    handler: (DockerWarning) -> Unit,
+    apiVersion: String,
+) = createClientPlugin("Warnings") {
+    // deprecated fields are reported once per client, as they're sent in every response
+    val reportedFields = mutableSetOf<String>()
+    val reportedFieldsMutex = Mutex()
+
+    onResponse { response ->
+        val method = response.call.request.method.value
+        val path = response.call.request.url.encodedPath
+        val requestId = response.call.attributes.getOrNull(RequestIdKey)
+        response.headers.getAll(HttpHeaders.Warning)?.forEach { value ->
+            handler(DockerWarning(message = parseWarningHeader(value), method = method, path = path, requestId = requestId))
+        }
+
+        val candidates = deprecatedFieldsFor(path, apiVersion)
+        if (candidates.isEmpty() || !response.status.isSuccess()) return@onResponse
+        if (response.contentType()?.match(ContentType.Application.Json) != true) return@onResponse
+
+        // inspect responses are never streamed, so their body is kept in memory and can be read again later
+        findDeprecatedFields(response.bodyAsText(), candidates).forEach { deprecated ->
+            val field = deprecated.field.joinToString(".")
+            if (reportedFieldsMutex.withLock { reportedFields.add(field) }) {
+                handler(
+                    DockerWarning(
+                        message = "$field is deprecated and no longer sent from API version ${deprecated.removedIn}",
+                        method = method,
+                        path = path,
+                        requestId = requestId,
+                    ),
+                )
+            }
+        }
+    }
+}
@@ -2,6 +2,9 @@ package me.devnatan.dockerkt
 
 import io.ktor.http.HttpStatusCode
 import kotlinx.serialization.Serializable
+import me.devnatan.dockerkt.io.CircuitBreaker
+import me.devnatan.dockerkt.io.DryRunRequest
+import kotlin.time.Duration
 
 public open class DockerException internal constructor(
     cause: Throwable?,
@@ -13,11 +16,59 @@ public open class DockerResourceException internal constructor(
     override val message: String? get() = null
 }
 
+/**
+ * Thrown when the Docker daemon responds with an error status code.
+ *
+ * @property statusCode The status code of the response.
+ * @property requestId The id the request was sent with, if request ids are enabled in the client configuration.
+ * @property contentEncoding The `Content-Encoding` the response body was sent with, if any. Gzip and deflate bodies
+ *                           are decoded before being parsed when response decompression is enabled in the client
+ *                           configuration, zstd bodies are never decoded.
+ * @property protocolVersion HTTP version of the response, e.g. `HTTP/1.1`, or `null` if no response was received.
+ */
 public class DockerResponseException internal constructor(
     cause: Throwable?,
     override val message: String?,
     public val statusCode: HttpStatusCode,
-) : DockerResourceException(cause)
+    public val requestId: String? = null,
+    public val contentEncoding: String? = null,
+    public val protocolVersion: String? = null,
+) : DockerResourceException(cause) {
+    /**
+     * Status line of the response, e.g. `HTTP/1.1 409 Conflict`, with the reason phrase sent by the Docker daemon.
+     * HTTP/2 responses have no reason phrase, e.g. `HTTP/2.0 409`.
+     */
+    public val statusLine: String
+        get() =
+            listOfNotNull(protocolVersion, statusCode.value.toString(), statusCode.description)
+                .filter(String::isNotBlank)
+                .joinToString(" ")
+}
+
+/**
+ * Thrown instead of sending a request when the client is in dry-run mode.
+ *
+ * @property request The request that would have been sent.
+ */
+public class DockerDryRunException internal constructor(
+    public val request: DryRunRequest,
+) : DockerException(null) {
+    override val message: String get() = "Dry run: ${request.method} ${request.url}"
+}
+
+/**
+ * Thrown instead of sending a request when the [CircuitBreaker] of the client is open for the target host, due to
+ * too many consecutive connection failures.
+ *
+ * @property host The host the circuit is open for.
+ * @property retryAfter Time left until calls are let through again.
+ */
+public class DockerCircuitOpenException internal constructor(
+    public val host: String,
+    public val retryAfter: Duration,
+) : DockerException(null) {
+    override val message: String get() = "Circuit breaker is open for $host, retry after $retryAfter"
+}
 
 @Serializable
 internal data class GenericDockerErrorResponse(
@@ -0,0 +1,7 @@
+package me.devnatan.dockerkt
+
+import io.ktor.util.logging.KtorSimpleLogger
+import io.ktor.util.logging.Logger
+
+// logs through SLF4J on the JVM, like the Ktor client does, and to the standard output on other platforms
+internal val DockerKotlinLogger: Logger = KtorSimpleLogger("me.devnatan.dockerkt")
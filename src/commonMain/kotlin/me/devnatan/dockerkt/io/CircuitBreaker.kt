@@ -0,0 +1,138 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.plugins.api.Send
+import io.ktor.client.plugins.api.createClientPlugin
+import kotlinx.coroutines.NonCancellable
+import kotlinx.coroutines.sync.Mutex
+import kotlinx.coroutines.sync.withLock
+import kotlinx.coroutines.withContext
+import me.devnatan.dockerkt.DockerCircuitOpenException
+import kotlin.coroutines.cancellation.CancellationException
+import kotlin.time.Duration
+import kotlin.time.Duration.Companion.seconds
+import kotlin.time.TimeMark
+import kotlin.time.TimeSource
+
+/**
+ * Circuit breaker that makes calls fail fast with a [DockerCircuitOpenException] while the Docker daemon is
+ * unreachable, instead of having every call wait for its own connection to fail.
+ *
+ * Only calls that get no response at all, e.g. due to a refused connection, count as failures. Error responses
+ * prove that the daemon is up and close the circuit.
+ *
+ * @property failureThreshold Amount of consecutive failures that opens the circuit.
+ * @property openDuration How long the circuit stays open before probe calls are let through.
+ * @property halfOpenProbes Amount of concurrent probe calls let through once [openDuration] has elapsed. The circuit
+ *                          closes if a probe succeeds, and opens again if one fails.
+ * @property timeSource Source of time, useful to control the open duration in tests.
+ */
+public data class CircuitBreaker(
+    val failureThreshold: Int = 5,
+    val openDuration: Duration = 30.seconds,
+    val halfOpenProbes: Int = 1,
+    val timeSource: TimeSource = TimeSource.Monotonic,
+) {
+    init {
+        require(failureThreshold > 0) { "Failure threshold must be positive" }
+        require(openDuration.isPositive()) { "Open duration must be positive" }
+        require(halfOpenProbes > 0) { "Half-open probes must be positive" }
+    }
+}
+
+private sealed class CircuitState {
+    data class Closed(
+        val failures: Int,
+    ) : CircuitState()
+
+    data class Open(
+        val openedAt: TimeMark,
+    ) : CircuitState()
+
+    data class HalfOpen(
+        val probes: Int,
+    ) : CircuitState()
+}
+
+/**
+ * State of the circuits of a client, keyed by the host calls are made to.
+ */
+private class Circuits(
+    private val breaker: CircuitBreaker,
+) {
+    private val mutex = Mutex()
+    private val states = mutableMapOf<String, CircuitState>()
+
+    suspend fun acquire(host: String) =
+        mutex.withLock {
+            when (val state = states[host] ?: CircuitState.Closed(0)) {
+                is CircuitState.Closed -> {}
+                is CircuitState.Open -> {
+                    val elapsed = state.openedAt.elapsedNow()
+                    if (elapsed < breaker.openDuration) {
+                        throw DockerCircuitOpenException(host, retryAfter = breaker.openDuration - elapsed)
+                    }
+                    states[host] = CircuitState.HalfOpen(probes = 1)
+                }
+                is CircuitState.HalfOpen -> {
+                    if (state.probes >= breaker.halfOpenProbes) {
+                        throw DockerCircuitOpenException(host, retryAfter = Duration.ZERO)
+                    }
+                    states[host] = CircuitState.HalfOpen(state.probes + 1)
+                }
+            }
+        }
+
+    suspend fun succeeded(host: String) =
+        mutex.withLock {
+            states[host] = CircuitState.Closed(0)
+        }
+
+    suspend fun released(host: String) =
+        mutex.withLock {
+            val state = states[host]
+            if (state is CircuitState.HalfOpen) {
+                states[host] = CircuitState.HalfOpen(state.probes - 1)
+            }
+        }
+
+    suspend fun failed(host: String) =
+        mutex.withLock {
+            val failures =
+                when (val state = states[host] ?: CircuitState.Closed(0)) {
+                    is CircuitState.Closed -> state.failures + 1
+                    else -> breaker.failureThreshold
+                }
+
+            states[host] =
+                if (failures >= breaker.failureThreshold) {
+                    CircuitState.Open(breaker.timeSource.markNow())
+                } else {
+                    CircuitState.Closed(failures)
+                }
+        }
+}
+
+internal fun createCircuitBreakerPlugin(breaker: CircuitBreaker) =
+    createClientPlugin("CircuitBreaker") {
+        val circuits = Circuits(breaker)
+
+        on(Send) { request ->
+            val host = request.url.host
+            circuits.acquire(host)
+
+            val call =
+                try {
+                    proceed(request)
+                } catch (e: CancellationException) {
+                    // a cancelled call says nothing about the daemon health
+                    withContext(NonCancellable) { circuits.released(host) }
+                    throw e
+                } catch (e: Throwable) {
+                    circuits.failed(host)
+                    throw e
+                }
+
+            circuits.succeeded(host)
+            call
+        }
+    }
@@ -0,0 +1,127 @@
+package me.devnatan.dockerkt.io
+
+private const val DockerTlsPort = 2376
+private const val SshPort = 22
+
+/**
+ * A Docker daemon endpoint, as set in the `DOCKER_HOST` environment variable.
+ */
+public sealed class DockerEndpoint {
+    /**
+     * Daemon listening on a Unix socket, e.g. `unix:///var/run/docker.sock`.
+     *
+     * @property path Path of the socket file.
+     */
+    public data class Unix(
+        val path: String,
+    ) : DockerEndpoint()
+
+    /**
+     * Daemon listening on a Windows named pipe, e.g. `npipe:////./pipe/docker_engine`.
+     *
+     * @property path Windows path of the named pipe, e.g. `\\.\pipe\docker_engine`.
+     */
+    public data class NamedPipe(
+        val path: String,
+    ) : DockerEndpoint()
+
+    /**
+     * Daemon listening on a TCP port, e.g. `tcp://localhost:2375`.
+     *
+     * @property host Hostname or IP address of the daemon.
+     * @property port Port the daemon listens on.
+     * @property tls Whether the endpoint hints that TLS is used, that is, the `https` scheme or the `2376` port.
+     */
+    public data class Tcp(
+        val host: String,
+        val port: Int,
+        val tls: Boolean,
+    ) : DockerEndpoint()
+
+    /**
+     * Daemon reached through SSH, e.g. `ssh://user@host`.
+     *
+     * @property user User to log in as, if any.
+     * @property host Hostname or IP address of the SSH server.
+     * @property port Port of the SSH server.
+     */
+    public data class Ssh(
+        val user: String?,
+        val host: String,
+        val port: Int,
+    ) : DockerEndpoint()
+
+    /**
+     * Daemon socket activated by systemd, e.g. `fd://`.
+     *
+     * @property descriptor Name of the socket passed by systemd, if any.
+     */
+    public data class Fd(
+        val descriptor: String?,
+    ) : DockerEndpoint()
+
+    public companion object {
+        /**
+         * Parses a Docker daemon endpoint with one of the `unix`, `npipe`, `tcp`, `http`, `https`, `ssh` or `fd`
+         * schemes, the ones supported by `DOCKER_HOST`. Schemes are matched regardless of their case.
+         *
+         * @param value The endpoint, e.g. `tcp://localhost:2375`.
+         * @throws IllegalArgumentException If the endpoint is not valid.
+         */
+        public fun parse(value: String): DockerEndpoint {
+            val scheme = value.substringBefore("://", missingDelimiterValue = "").lowercase()
+            require(scheme.isNotEmpty()) { "Missing scheme in Docker endpoint: $value" }
+
+            val rest = value.substring(scheme.length + 3)
+            return when (scheme) {
+                "unix" -> {
+                    require(rest.isNotBlank()) { "Missing socket path in Docker endpoint: $value" }
+                    Unix(rest)
+                }
+                "npipe" -> {
+                    require(rest.isNotBlank()) { "Missing named pipe path in Docker endpoint: $value" }
+                    // npipe:////./pipe/docker_engine is \\.\pipe\docker_engine
+                    NamedPipe(rest.replace('/', '\\'))
+                }
+                "tcp", "http", "https" -> {
+                    val (host, port) = parseAuthority(value, rest.substringBefore('/'))
+                    val tls = scheme == "https" || port == DockerTlsPort
+                    Tcp(host, port ?: if (tls) DockerTlsPort else DockerSocketPort, tls)
+                }
+                "ssh" -> {
+                    val authority = rest.substringBefore('/')
+                    val user = authority.substringBefore('@', missingDelimiterValue = "").ifEmpty { null }
+                    val (host, port) = parseAuthority(value, authority.substringAfter('@'))
+                    Ssh(user, host, port ?: SshPort)
+                }
+                "fd" -> Fd(rest.ifEmpty { null })
+                else -> throw IllegalArgumentException("Unsupported scheme \"$scheme\" in Docker endpoint: $value")
+            }
+        }
+
+        private fun parseAuthority(
+            value: String,
+            authority: String,
+        ): Pair<String, Int?> {
+            // IPv6 addresses are enclosed in brackets, e.g. [::1]:2375
+            val hostEnd =
+                if (authority.startsWith('[')) {
+                    authority.indexOf(']') + 1
+                } else {
+                    authority.indexOf(':').takeIf { it >= 0 } ?: authority.length
+                }
+            val host = authority.substring(0, hostEnd).removeSurrounding("[", "]")
+            require(host.isNotEmpty()) { "Missing host in Docker endpoint: $value" }
+
+            val port =
+                authority.substring(hostEnd).takeIf { it.isNotEmpty() }?.let { rawPort ->
+                    val port = rawPort.removePrefix(":").toIntOrNull()
+                    require(rawPort.startsWith(':') && port != null && port in 1..65535) {
+                        "Invalid port in Docker endpoint: $value"
+                    }
+                    port
+                }
+            return host to port
+        }
+    }
+}
@@ -0,0 +1,73 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.engine.HttpClientEngineBase
+import io.ktor.client.engine.HttpClientEngineConfig
+import io.ktor.client.request.HttpRequestData
+import io.ktor.client.request.HttpResponseData
+import io.ktor.http.HttpHeaders
+import io.ktor.http.content.ByteArrayContent
+import io.ktor.http.content.OutgoingContent
+import io.ktor.http.content.TextContent
+import io.ktor.utils.io.InternalAPI
+import me.devnatan.dockerkt.DockerDryRunException
+
+/**
+ * A fully built HTTP request that was not sent because the client is in dry-run mode.
+ *
+ * @property method HTTP method of the request.
+ * @property url Final URL of the request, including the encoded query.
+ * @property headers All headers of the request, including the ones added by the client.
+ * @property body Request body, if it's textual, or a description of it otherwise.
+ */
+public data class DryRunRequest(
+    val method: String,
+    val url: String,
+    val headers: Map<String, List<String>>,
+    val body: String?,
+) {
+    /**
+     * Returns the HTTP/1.1 representation of this request as it would have been written to the wire.
+     */
+    public fun toWireFormat(): String =
+        buildString {
+            val target = url.substringAfter("://").let { rest -> "/" + rest.substringAfter("/", "") }
+            append("$method $target HTTP/1.1\r\n")
+            headers.forEach { (name, values) ->
+                values.forEach { value -> append("$name: $value\r\n") }
+            }
+            append("\r\n")
+            body?.let(::append)
+        }
+}
+
+/**
+ * Engine that never sends requests, failing every call with a [DockerDryRunException] holding the request.
+ */
+internal class DryRunEngine : HttpClientEngineBase("docker-kotlin-dry-run") {
+    override val config: HttpClientEngineConfig = HttpClientEngineConfig()
+
+    @InternalAPI
+    override suspend fun execute(data: HttpRequestData): HttpResponseData {
+        val content = data.body
+        val headers = data.headers.entries().associate { (name, values) -> name to values }.toMutableMap()
+        content.contentType?.let { contentType -> headers[HttpHeaders.ContentType] = listOf(contentType.toString()) }
+        content.contentLength?.let { length -> headers[HttpHeaders.ContentLength] = listOf(length.toString()) }
+
+        val body =
+            when (content) {
+                is TextContent -> content.text
+                is ByteArrayContent -> "<${content.bytes().size} bytes>"
+                is OutgoingContent.NoContent -> null
+                else -> content.contentLength?.let { length -> "<streamed body of $length bytes>" } ?: "<streamed body>"
+            }
+
+        throw DockerDryRunException(
+            DryRunRequest(
+                method = data.method.value,
+                url = data.url.toString(),
+                headers = headers,
+                body = body,
+            ),
+        )
+    }
+}
@@ -0,0 +1,84 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.call.wrapWithContent
+import io.ktor.client.plugins.api.Send
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.http.HttpStatusCode
+import io.ktor.utils.io.InternalAPI
+import io.ktor.utils.io.cancel
+import io.ktor.utils.io.copyTo
+import io.ktor.utils.io.writer
+import kotlinx.coroutines.delay
+import kotlinx.io.IOException
+import me.devnatan.dockerkt.DockerResponseException
+import kotlin.random.Random
+import kotlin.time.Duration
+
+/**
+ * Faults injected in the HTTP calls of a client, so that retry and reconnection behavior can be tested without
+ * depending on a misbehaving Docker daemon.
+ *
+ * All rates are probabilities between `0.0` (never) and `1.0` (always), rolled independently for each call.
+ *
+ * @property latency Delay added before every call is sent.
+ * @property connectionDropRate Rate of calls that fail with an [IOException] as if the connection was dropped.
+ * @property errorRate Rate of calls that fail with a [DockerResponseException] of [errorStatus] without being sent.
+ * @property errorStatus Status code of the injected error responses.
+ * @property truncateRate Rate of responses whose body ends early, after [truncateAfterBytes] bytes.
+ * @property truncateAfterBytes Amount of bytes read from a truncated response body before it ends.
+ * @property random Source of randomness, useful to get reproducible runs using a seeded [Random].
+ */
+public data class FaultInjection(
+    val latency: Duration = Duration.ZERO,
+    val connectionDropRate: Double = 0.0,
+    val errorRate: Double = 0.0,
+    val errorStatus: HttpStatusCode = HttpStatusCode.ServiceUnavailable,
+    val truncateRate: Double = 0.0,
+    val truncateAfterBytes: Long = 0,
+    val random: Random = Random.Default,
+) {
+    init {
+        require(connectionDropRate in 0.0..1.0) { "Connection drop rate must be between 0.0 and 1.0" }
+        require(errorRate in 0.0..1.0) { "Error rate must be between 0.0 and 1.0" }
+        require(truncateRate in 0.0..1.0) { "Truncate rate must be between 0.0 and 1.0" }
+        require(truncateAfterBytes >= 0) { "Truncate after bytes cannot be negative" }
+    }
+}
+
+private fun FaultInjection.roll(rate: Double): Boolean = rate > 0.0 && random.nextDouble() < rate
+
+@OptIn(InternalAPI::class)
+internal fun createFaultInjectionPlugin(faults: FaultInjection) =
+    createClientPlugin("FaultInjection") {
+        on(Send) { request ->
+            if (faults.latency.isPositive()) {
+                delay(faults.latency)
+            }
+
+            if (faults.roll(faults.connectionDropRate)) {
+                throw IOException("Connection dropped by fault injection")
+            }
+
+            if (faults.roll(faults.errorRate)) {
+                throw DockerResponseException(
+                    cause = null,
+                    message = "Error injected by fault injection",
+                    statusCode = faults.errorStatus,
+                )
+            }
+
+            val call = proceed(request)
+            if (!faults.roll(faults.truncateRate)) {
+                return@on call
+            }
+
+            val original = call.response.rawContent
+            val truncated =
+                client
+                    .writer {
+                        original.copyTo(channel, faults.truncateAfterBytes)
+                        original.cancel()
+                    }.channel
+            call.wrapWithContent(truncated)
+        }
+    }
@@ -0,0 +1,36 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.http.ContentType
+import io.ktor.http.content.OutgoingContent
+import io.ktor.utils.io.ByteWriteChannel
+import io.ktor.utils.io.writeFully
+import kotlinx.io.buffered
+import kotlinx.io.files.Path
+import kotlinx.io.files.SystemFileSystem
+
+private const val ReadBufferSize = 8192
+
+/**
+ * Request body that streams the file at [path] instead of reading it entirely into memory.
+ *
+ * Content-Length is always set since some Docker endpoints reject chunked bodies on Ktor CIO.
+ */
+internal class FileContent(
+    private val path: Path,
+    override val contentType: ContentType,
+) : OutgoingContent.WriteChannelContent() {
+    override val contentLength: Long =
+        requireNotNull(FileSystemUtils.getMetadata(path)) { "File not found: $path" }.size
+
+    override suspend fun writeTo(channel: ByteWriteChannel) {
+        SystemFileSystem.source(path).buffered().use { source ->
+            val buffer = ByteArray(ReadBufferSize)
+            while (true) {
+                val read = source.readAtMostTo(buffer, 0, buffer.size)
+                if (read == -1) break
+
+                channel.writeFully(buffer, 0, read)
+            }
+        }
+    }
+}
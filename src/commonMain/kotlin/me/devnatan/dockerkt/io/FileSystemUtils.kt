@@ -1,5 +1,7 @@
 package me.devnatan.dockerkt.io
 
+import io.ktor.utils.io.ByteReadChannel
+import io.ktor.utils.io.readAvailable
 import kotlinx.io.buffered
 import kotlinx.io.files.FileMetadata
 import kotlinx.io.files.Path
@@ -9,6 +11,8 @@ import kotlinx.io.readByteArray
 import kotlin.time.Clock
 import kotlin.time.ExperimentalTime
 
+private const val CopyBufferSize = 8192
+
 /** Multiplatform file operations wrapper. */
 internal object FileSystemUtils {
     private val fs = SystemFileSystem
@@ -25,6 +29,38 @@ internal object FileSystemUtils {
         sink.write(data)
     }
 
+    fun appendFile(
+        path: Path,
+        data: ByteArray,
+    ) = fs.sink(path, append = true).buffered().use { sink ->
+        sink.write(data)
+    }
+
+    /**
+     * Writes everything read from [channel] to the file at [path] without buffering it entirely in memory.
+     *
+     * @param onProgress Called with the total amount of bytes written so far after each chunk.
+     * @return The total amount of bytes written.
+     */
+    suspend fun writeChannel(
+        path: Path,
+        channel: ByteReadChannel,
+        onProgress: ((Long) -> Unit)? = null,
+    ): Long =
+        fs.sink(path).buffered().use { sink ->
+            val buffer = ByteArray(CopyBufferSize)
+            var written = 0L
+            while (true) {
+                val read = channel.readAvailable(buffer, 0, buffer.size)
+                if (read == -1) break
+
+                sink.write(buffer, 0, read)
+                written += read
+                onProgress?.invoke(written)
+            }
+            written
+        }
+
     fun createDirectories(path: Path) {
         fs.createDirectories(path)
     }
@@ -8,17 +8,24 @@ import io.ktor.client.engine.HttpClientEngineFactory
 import io.ktor.client.plugins.HttpResponseValidator
 import io.ktor.client.plugins.ResponseException
 import io.ktor.client.plugins.UserAgent
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.client.plugins.cache.HttpCache
+import io.ktor.client.plugins.compression.ContentEncoding
+import io.ktor.client.plugins.compression.appliedDecoders
 import io.ktor.client.plugins.contentnegotiation.ContentNegotiation
+import io.ktor.client.plugins.cookies.HttpCookies
 import io.ktor.client.plugins.defaultRequest
 import io.ktor.client.plugins.logging.LogLevel
 import io.ktor.client.plugins.logging.Logger
 import io.ktor.client.plugins.logging.Logging
 import io.ktor.client.plugins.logging.SIMPLE
+import io.ktor.client.plugins.websocket.WebSockets
+import io.ktor.client.statement.HttpResponse
 import io.ktor.http.ContentType
+import io.ktor.http.HttpHeaders
 import io.ktor.http.HttpStatusCode
 import io.ktor.http.URLBuilder
 import io.ktor.http.URLProtocol
-import io.ktor.http.Url
 import io.ktor.http.contentType
 import io.ktor.http.encodedPath
 import io.ktor.http.takeFrom
@@ -27,14 +34,47 @@ import kotlinx.serialization.json.Json
 import me.devnatan.dockerkt.DockerClient
 import me.devnatan.dockerkt.DockerResponseException
 import me.devnatan.dockerkt.GenericDockerErrorResponse
+import me.devnatan.dockerkt.createMetricsPlugin
+import me.devnatan.dockerkt.createResponseListenerPlugin
+import me.devnatan.dockerkt.createWarningsPlugin
+
+// WebSocket calls are made with the ws scheme, which must follow the client TLS configuration like https does
+private val SecureWebSockets =
+    createClientPlugin("SecureWebSockets") {
+        onRequest { request, _ ->
+            if (request.url.protocol == URLProtocol.WS) {
+                request.url.protocol = URLProtocol.WSS
+            }
+        }
+    }
 
 internal expect val defaultHttpClientEngine: HttpClientEngineFactory<*>?
 
+/**
+ * Whether the engine reports the details of the calls that only it knows, like timings and connection addresses.
+ */
+internal expect val engineReportsCallInfo: Boolean
+
 internal expect fun <T : HttpClientEngineConfig> HttpClientConfig<out T>.configureHttpClient(client: DockerClient)
 
 internal fun createHttpClient(client: DockerClient): HttpClient {
     check(client.config.socketPath.isNotBlank()) { "Socket path cannot be blank" }
 
+    val fixtures = client.config.httpFixtures
+    val offlineEngine =
+        when {
+            client.config.dryRun -> DryRunEngine()
+            fixtures is HttpFixtures.Replay -> HttpFixturesReplayEngine(fixtures.cassettePath)
+            else -> null
+        }
+
+    if (offlineEngine != null) {
+        // these engines never reach the Docker daemon so the platform engine is not needed at all
+        return HttpClient(offlineEngine) {
+            configure(client, configureEngine = false)
+        }
+    }
+
     val clientEngine = defaultHttpClientEngine
     return if (clientEngine != null) {
         HttpClient(clientEngine) { configure(client) }
@@ -43,8 +83,13 @@ internal fun createHttpClient(client: DockerClient): HttpClient {
     }
 }
 
-private fun HttpClientConfig<*>.configure(client: DockerClient) {
+private fun HttpClientConfig<*>.configure(
+    client: DockerClient,
+    configureEngine: Boolean = true,
+) {
     expectSuccess = true
+    // redirects are followed by the Redirect plugin instead, according to the client redirect policy
+    followRedirects = false
 
     install(ContentNegotiation) {
         json(
@@ -61,11 +106,81 @@ private fun HttpClientConfig<*>.configure(client: DockerClient) {
         }
     }
 
-    install(UserAgent) { agent = "docker-kotlin" }
-    configureHttpClient(client)
+    install(UserAgent) { agent = client.config.userAgent }
+    if (client.config.decompressResponses) {
+        install(ContentEncoding) {
+            gzip()
+            deflate()
+        }
+    }
+
+    install(WebSockets)
+    client.cookieJar?.let { jar ->
+        install(HttpCookies) { storage = jar }
+    }
+    if (client.config.responseCache) {
+        install(HttpCache) {
+            publicStorage(LruCacheStorage())
+            privateStorage(LruCacheStorage())
+        }
+        install(NotModifiedMarker)
+    }
+    if (client.config.tls != null) {
+        install(SecureWebSockets)
+    }
+
+    if (configureEngine) {
+        configureHttpClient(client)
+    }
+
+    install(createMetricsPlugin(client.metricsRecorder))
+    install(createWarningsPlugin(client.config.warningHandler, client.config.apiVersion))
+
+    client.config.responseListener?.let { listener ->
+        // offline engines never connect anywhere, so there's nothing to report
+        install(
+            createResponseListenerPlugin(
+                listener = listener,
+                callTimings = client.config.callTimings,
+                engineReportsCallInfo = configureEngine && engineReportsCallInfo,
+            ),
+        )
+    }
+
+    install(createRedirectPlugin(client.config.redirectPolicy))
+
+    // every attempt goes through the circuit breaker, so retries stop as soon as it opens
+    client.config.retryPolicy?.let { policy ->
+        install(createRetryPlugin(policy))
+    }
+
+    client.config.circuitBreaker?.let { breaker ->
+        install(createCircuitBreakerPlugin(breaker))
+    }
+
+    val fixtures = client.config.httpFixtures
+    if (fixtures is HttpFixtures.Record) {
+        install(createHttpFixturesRecorder(fixtures.cassettePath))
+    }
+
+    client.config.trafficDump?.let { dump ->
+        install(createTrafficDumpPlugin(dump))
+    }
+
+    client.config.faultInjection?.let { faults ->
+        install(createFaultInjectionPlugin(faults))
+    }
+
+    client.config.tracePropagation?.let { tracing ->
+        install(createTracePropagationPlugin(tracing))
+    }
+
+    client.config.requestIdHeader?.let { headerName ->
+        install(createRequestIdPlugin(headerName))
+    }
 
     HttpResponseValidator {
-        handleResponseExceptionWithRequest { exception, _ ->
+        handleResponseExceptionWithRequest { exception, request ->
             val responseException = exception as? ResponseException ?: return@handleResponseExceptionWithRequest
             val exceptionResponse = responseException.response
             println("exceptionResponse = ${exceptionResponse.body<String>()}")
@@ -78,17 +193,22 @@ private fun HttpClientConfig<*>.configure(client: DockerClient) {
                 cause = exception,
                 message = errorMessage,
                 statusCode = exceptionResponse.status,
+                requestId = request.attributes.getOrNull(RequestIdKey),
+                contentEncoding = exceptionResponse.originalContentEncoding(),
+                protocolVersion = exceptionResponse.version.toString(),
             )
         }
     }
 
+    val baseUrl = createUrlBuilder(client.config.socketPath, tls = client.config.tls != null)
     defaultRequest {
         contentType(ContentType.Application.Json)
+        client.config.defaultHeaders.forEach { (name, values) -> headers.appendAll(name, values) }
 
         // workaround for URL prepending
         // https://github.com/ktorio/ktor/issues/537#issuecomment-603272476
         url.takeFrom(
-            URLBuilder(createUrlBuilder(client.config.socketPath)).apply {
+            URLBuilder(baseUrl).apply {
                 encodedPath = "/v${client.config.apiVersion}/"
                 encodedPath += url.encodedPath
             },
@@ -96,20 +216,31 @@ private fun HttpClientConfig<*>.configure(client: DockerClient) {
     }
 }
 
-private fun createUrlBuilder(socketPath: String): URLBuilder =
-    if (isUnixSocket(socketPath)) {
-        URLBuilder(
-            protocol = URLProtocol.HTTP,
-            port = DockerSocketPort,
-            host = encodeSocketPathHostname(socketPath.substringAfter(UnixSocketPrefix)),
-        )
-    } else {
-        val url = Url(socketPath)
-        URLBuilder(
-            protocol = URLProtocol.HTTP,
-            host = url.host,
-            port = url.port,
-        )
+private fun createUrlBuilder(
+    socketPath: String,
+    tls: Boolean,
+): URLBuilder =
+    when (val endpoint = DockerEndpoint.parse(socketPath)) {
+        is DockerEndpoint.Unix ->
+            URLBuilder(
+                protocol = URLProtocol.HTTP,
+                port = DockerSocketPort,
+                host = encodeSocketPathHostname(endpoint.path),
+            )
+        is DockerEndpoint.NamedPipe ->
+            URLBuilder(
+                protocol = URLProtocol.HTTP,
+                port = DockerSocketPort,
+                host = encodeSocketPathHostname(endpoint.path),
+            )
+        is DockerEndpoint.Tcp ->
+            URLBuilder(
+                protocol = if (tls) URLProtocol.HTTPS else URLProtocol.HTTP,
+                host = endpoint.host,
+                port = endpoint.port,
+            )
+        is DockerEndpoint.Ssh, is DockerEndpoint.Fd ->
+            throw IllegalArgumentException("Unsupported Docker endpoint: $socketPath")
     }
 
 internal fun handleHttpFailure(
@@ -132,6 +263,13 @@ internal fun handleHttpFailure(
         ?: exception
 }
 
+/**
+ * Returns the `Content-Encoding` this response was sent with, even if its body was already decoded.
+ */
+internal fun HttpResponse.originalContentEncoding(): String? =
+    // the header may be dropped once the body is decoded
+    headers[HttpHeaders.ContentEncoding] ?: appliedDecoders.joinToString().ifEmpty { null }
+
 // TODO use Ktor exception handler instead
 internal inline fun <T> requestCatching(
     vararg errors: Pair<HttpStatusCode, (DockerResponseException) -> Throwable>,
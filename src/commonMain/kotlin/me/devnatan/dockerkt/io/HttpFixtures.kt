@@ -0,0 +1,188 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.call.save
+import io.ktor.client.engine.HttpClientEngineBase
+import io.ktor.client.engine.HttpClientEngineConfig
+import io.ktor.client.engine.callContext
+import io.ktor.client.plugins.api.Send
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.client.request.HttpRequestData
+import io.ktor.client.request.HttpResponseData
+import io.ktor.client.statement.readRawBytes
+import io.ktor.http.Headers
+import io.ktor.http.HttpProtocolVersion
+import io.ktor.http.HttpStatusCode
+import io.ktor.http.content.ByteArrayContent
+import io.ktor.http.content.OutgoingContent
+import io.ktor.http.content.TextContent
+import io.ktor.http.headers
+import io.ktor.util.date.GMTDate
+import io.ktor.util.decodeBase64Bytes
+import io.ktor.util.encodeBase64
+import io.ktor.utils.io.ByteReadChannel
+import io.ktor.utils.io.InternalAPI
+import kotlinx.coroutines.sync.Mutex
+import kotlinx.coroutines.sync.withLock
+import kotlinx.io.files.Path
+import kotlinx.serialization.Serializable
+import me.devnatan.dockerkt.util.DockerKotlinJson
+
+/**
+ * Mode of the HTTP fixtures subsystem, used to record request/response pairs to a cassette file and replay them
+ * later without a Docker daemon. Cassettes hold one JSON encoded request/response pair per line.
+ *
+ * @property cassettePath Path of the cassette file on the local filesystem.
+ */
+public sealed class HttpFixtures {
+    public abstract val cassettePath: String
+
+    /**
+     * Performs requests against the Docker daemon as usual, saving every request/response pair to the cassette.
+     *
+     * Responses are fully buffered before being recorded, so streaming calls that never end such as following
+     * logs or events cannot be recorded.
+     */
+    public data class Record(
+        override val cassettePath: String,
+    ) : HttpFixtures()
+
+    /**
+     * Never contacts the Docker daemon, answering every request with the recorded response of the first unused
+     * interaction in the cassette with the same method, path, query and body.
+     */
+    public data class Replay(
+        override val cassettePath: String,
+    ) : HttpFixtures()
+}
+
+@Serializable
+internal data class Interaction(
+    val request: RecordedRequest,
+    val response: RecordedResponse,
+)
+
+@Serializable
+internal data class RecordedRequest(
+    val method: String,
+    val path: String,
+    val body: String? = null,
+)
+
+/**
+ * @property body Response body, if it's textual.
+ * @property base64Body Base64 encoded response body, if it's binary.
+ */
+@Serializable
+internal data class RecordedResponse(
+    val status: Int,
+    val headers: Map<String, List<String>> = emptyMap(),
+    val body: String? = null,
+    val base64Body: String? = null,
+)
+
+/**
+ * Encodes [interaction] as a line of a cassette file, which holds one JSON encoded interaction per line so that
+ * recorded interactions can be appended to it.
+ */
+internal fun encodeInteraction(interaction: Interaction): String = DockerKotlinJson.encodeToString(interaction) + "\n"
+
+private fun readCassette(path: Path): List<Interaction> =
+    if (FileSystemUtils.exists(path)) {
+        FileSystemUtils
+            .readFile(path)
+            .decodeToString()
+            .lineSequence()
+            .filter(String::isNotBlank)
+            .map { line -> DockerKotlinJson.decodeFromString<Interaction>(line) }
+            .toList()
+    } else {
+        emptyList()
+    }
+
+// textual bodies are recorded as is and binary ones base64 encoded, streamed ones can't be recorded at all
+private fun encodeRequestBody(content: OutgoingContent): String? =
+    when (content) {
+        is TextContent -> content.text
+        is ByteArrayContent -> content.bytes().encodeBase64()
+        else -> null
+    }
+
+private fun isTextual(headers: Headers): Boolean {
+    val contentType = headers["Content-Type"] ?: return true
+    return contentType.startsWith("application/json") || contentType.startsWith("text/")
+}
+
+internal fun createHttpFixturesRecorder(cassettePath: String) =
+    createClientPlugin("HttpFixturesRecorder") {
+        val path = Path(cassettePath)
+        val lock = Mutex()
+
+        on(Send) { request ->
+            val call = proceed(request).save()
+            val response = call.response
+            val body = response.readRawBytes()
+            val textual = isTextual(response.headers)
+
+            val recordedRequest =
+                RecordedRequest(
+                    method = request.method.value,
+                    path = request.url.build().encodedPathAndQuery,
+                    body = (request.body as? OutgoingContent)?.let(::encodeRequestBody),
+                )
+            val recordedResponse =
+                RecordedResponse(
+                    status = response.status.value,
+                    headers = response.headers.entries().associate { (name, values) -> name to values },
+                    body = if (textual) body.decodeToString() else null,
+                    base64Body = if (textual) null else body.encodeBase64(),
+                )
+
+            lock.withLock {
+                FileSystemUtils.appendFile(
+                    path,
+                    encodeInteraction(Interaction(recordedRequest, recordedResponse)).encodeToByteArray(),
+                )
+            }
+            call
+        }
+    }
+
+/**
+ * Engine that answers requests with the interactions recorded in a cassette file.
+ */
+internal class HttpFixturesReplayEngine(
+    cassettePath: String,
+) : HttpClientEngineBase("docker-kotlin-replay") {
+    override val config: HttpClientEngineConfig = HttpClientEngineConfig()
+
+    private val remaining = readCassette(Path(cassettePath)).toMutableList()
+    private val lock = Mutex()
+
+    @OptIn(InternalAPI::class)
+    override suspend fun execute(data: HttpRequestData): HttpResponseData {
+        val callContext = callContext()
+        val method = data.method.value
+        val path = data.url.encodedPathAndQuery
+        val body = encodeRequestBody(data.body)
+        val interaction =
+            lock.withLock {
+                val index =
+                    remaining.indexOfFirst {
+                        it.request.method == method && it.request.path == path && it.request.body == body
+                    }
+                check(index != -1) { "No recorded interaction left for $method $path" }
+                remaining.removeAt(index)
+            }
+
+        val response = interaction.response
+        val body = response.base64Body?.decodeBase64Bytes() ?: response.body.orEmpty().encodeToByteArray()
+        return HttpResponseData(
+            HttpStatusCode.fromValue(response.status),
+            GMTDate(),
+            headers { response.headers.forEach { (name, values) -> appendAll(name, values) } },
+            HttpProtocolVersion.HTTP_1_1,
+            ByteReadChannel(body),
+            callContext,
+        )
+    }
+}
@@ -0,0 +1,88 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.plugins.api.Send
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.client.request.HttpRequestBuilder
+import io.ktor.client.request.takeFrom
+import io.ktor.http.HttpHeaders
+import io.ktor.http.HttpMethod
+import io.ktor.http.HttpStatusCode
+import io.ktor.http.URLBuilder
+import io.ktor.http.takeFrom
+import io.ktor.util.AttributeKey
+import io.ktor.utils.io.InternalAPI
+import io.ktor.utils.io.cancel
+
+// Ktor only follows redirects of methods without a request body as well
+private val RedirectableMethods = setOf(HttpMethod.Get, HttpMethod.Head)
+
+private val RedirectStatuses =
+    setOf(
+        HttpStatusCode.MovedPermanently,
+        HttpStatusCode.Found,
+        HttpStatusCode.SeeOther,
+        HttpStatusCode.TemporaryRedirect,
+        HttpStatusCode.PermanentRedirect,
+    )
+
+internal val RedirectHistoryKey = AttributeKey<List<String>>("RedirectHistory")
+
+/**
+ * Which redirects sent by the Docker daemon, or a reverse proxy in front of it, are followed.
+ *
+ * Only redirects of `GET` and `HEAD` calls are followed. Redirects that are not followed fail the call with a
+ * `DockerResponseException` of the redirect status. Credential headers, like `Authorization` and `X-Registry-Auth`,
+ * are never sent to another host than the one of the original call.
+ *
+ * @property maxRedirects Maximum amount of redirects followed by a single call, `0` to follow none.
+ * @property sameHostOnly Whether only redirects to the host of the original call are followed.
+ */
+public data class RedirectPolicy(
+    val maxRedirects: Int = 20,
+    val sameHostOnly: Boolean = false,
+) {
+    init {
+        require(maxRedirects >= 0) { "Max redirects cannot be negative" }
+    }
+
+    public companion object {
+        /**
+         * Policy that never follows redirects.
+         */
+        public val None: RedirectPolicy = RedirectPolicy(maxRedirects = 0)
+    }
+}
+
+@OptIn(InternalAPI::class)
+internal fun createRedirectPlugin(policy: RedirectPolicy) =
+    createClientPlugin("Redirect") {
+        on(Send) { request ->
+            var call = proceed(request)
+            if (request.method !in RedirectableMethods) return@on call
+
+            val originalHost = request.url.host
+            val history = mutableListOf<String>()
+            while (history.size < policy.maxRedirects && call.response.status in RedirectStatuses) {
+                val location = call.response.headers[HttpHeaders.Location] ?: break
+                val target = URLBuilder(call.request.url).takeFrom(location)
+                if (policy.sameHostOnly && target.host != originalHost) break
+
+                history += call.request.url.toString()
+                val redirected =
+                    HttpRequestBuilder().apply {
+                        takeFrom(request)
+                        url.takeFrom(target)
+                        attributes.put(RedirectHistoryKey, history.toList())
+                        if (target.host != originalHost) {
+                            DefaultRedactedHeaders.forEach(headers::remove)
+                        }
+                    }
+
+                // the body of the redirect response is never read, releases its connection
+                call.response.rawContent.cancel()
+                call = proceed(redirected)
+            }
+
+            call
+        }
+    }
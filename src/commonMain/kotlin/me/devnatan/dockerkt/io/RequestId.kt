@@ -0,0 +1,27 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.client.request.header
+import io.ktor.util.AttributeKey
+import kotlin.uuid.ExperimentalUuidApi
+import kotlin.uuid.Uuid
+
+/**
+ * Default name of the header request ids are sent in.
+ */
+public const val DefaultRequestIdHeader: String = "X-Request-ID"
+
+/**
+ * Id the request of a call was sent with, if request ids are enabled in the client configuration.
+ */
+internal val RequestIdKey = AttributeKey<String>("RequestId")
+
+@OptIn(ExperimentalUuidApi::class)
+internal fun createRequestIdPlugin(headerName: String) =
+    createClientPlugin("RequestId") {
+        onRequest { request, _ ->
+            // an id set explicitly on the request takes precedence
+            val id = request.headers[headerName] ?: Uuid.random().toString().also { id -> request.header(headerName, id) }
+            request.attributes.put(RequestIdKey, id)
+        }
+    }
@@ -0,0 +1,70 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.client.plugins.cache.storage.CacheStorage
+import io.ktor.client.plugins.cache.storage.CachedResponseData
+import io.ktor.client.statement.HttpReceivePipeline
+import io.ktor.http.HttpStatusCode
+import io.ktor.http.Url
+import io.ktor.util.AttributeKey
+import kotlinx.coroutines.sync.Mutex
+import kotlinx.coroutines.sync.withLock
+
+/**
+ * Maximum amount of URLs whose responses are kept by each storage of the response cache.
+ */
+internal const val MaxCachedUrls = 256
+
+/**
+ * Set on calls the Docker daemon answered with `304 Not Modified`, whose response was served from the cache.
+ */
+internal val NotModifiedKey = AttributeKey<Unit>("NotModified")
+
+// runs before the cache replaces the 304 response with the cached one, the only point the daemon response is known
+internal val NotModifiedMarker =
+    createClientPlugin("NotModifiedMarker") {
+        client.receivePipeline.intercept(HttpReceivePipeline.Before) { response ->
+            if (response.status == HttpStatusCode.NotModified) {
+                response.call.attributes.put(NotModifiedKey, Unit)
+            }
+        }
+    }
+
+/**
+ * Response cache storage keeping the responses of at most [maxUrls] URLs, evicting the least recently used ones.
+ * Operations that are not overridden are no-ops.
+ */
+internal class LruCacheStorage(
+    private val maxUrls: Int = MaxCachedUrls,
+) : CacheStorage by CacheStorage.Disabled {
+    private val mutex = Mutex()
+
+    // in access order, from the least to the most recently used
+    private val responses = LinkedHashMap<Url, List<CachedResponseData>>()
+
+    override suspend fun store(
+        url: Url,
+        data: CachedResponseData,
+    ) = mutex.withLock {
+        // a response replaces the one of the same URL that varies the same way
+        val stored = responses.remove(url).orEmpty().filterNot { cached -> cached.varyKeys == data.varyKeys }
+        responses[url] = stored + data
+
+        while (responses.size > maxUrls) {
+            responses.remove(responses.keys.first())
+        }
+    }
+
+    override suspend fun findAll(url: Url): Set<CachedResponseData> = mutex.withLock { touch(url).toSet() }
+
+    override suspend fun find(
+        url: Url,
+        varyKeys: Map<String, String>,
+    ): CachedResponseData? = mutex.withLock { touch(url).find { cached -> cached.varyKeys == varyKeys } }
+
+    private fun touch(url: Url): List<CachedResponseData> {
+        val stored = responses.remove(url) ?: return emptyList()
+        responses[url] = stored
+        return stored
+    }
+}
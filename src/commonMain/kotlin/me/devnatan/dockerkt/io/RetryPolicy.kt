@@ -0,0 +1,161 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.plugins.api.Send
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.client.statement.HttpResponse
+import io.ktor.http.HttpHeaders
+import io.ktor.http.HttpMethod
+import io.ktor.http.HttpStatusCode
+import io.ktor.http.content.OutgoingContent
+import io.ktor.http.fromHttpToGmtDate
+import io.ktor.util.date.GMTDate
+import io.ktor.utils.io.InternalAPI
+import io.ktor.utils.io.cancel
+import kotlinx.coroutines.currentCoroutineContext
+import kotlinx.coroutines.delay
+import kotlinx.io.IOException
+import me.devnatan.dockerkt.DockerDeadline
+import me.devnatan.dockerkt.DockerResponseException
+import me.devnatan.dockerkt.withDeadline
+import kotlin.math.pow
+import kotlin.random.Random
+import kotlin.time.Duration
+import kotlin.time.Duration.Companion.milliseconds
+import kotlin.time.Duration.Companion.minutes
+import kotlin.time.Duration.Companion.seconds
+
+// methods that can be sent again without changing the outcome, see RFC 9110 section 9.2.2
+private val IdempotentMethods = setOf(HttpMethod.Get, HttpMethod.Head, HttpMethod.Put, HttpMethod.Delete, HttpMethod.Options)
+
+/**
+ * Retries calls that failed due to a transient condition, like the Docker daemon being restarted, with an exponential
+ * backoff between attempts.
+ *
+ * Only calls with an idempotent method (`GET`, `HEAD`, `PUT`, `DELETE` and `OPTIONS`) are retried, and never the
+ * ones whose request body can only be sent once, like a streamed image load.
+ *
+ * @property maxAttempts Maximum amount of attempts of each call, including the first one.
+ * @property baseDelay Delay before the first retry, doubled on every next one.
+ * @property maxDelay Upper bound of the delay between attempts.
+ * @property jitter Fraction of each delay that is randomized, between `0.0` (none) and `1.0` (the whole delay), so
+ *                  that clients started together don't retry in lockstep.
+ * @property retryOnConnectionErrors Whether calls that got no response at all, e.g. due to a refused connection, are
+ *                                   retried.
+ * @property retryOnStatuses Response status codes that are retried.
+ * @property random Source of randomness of the jitter, useful to get reproducible delays using a seeded [Random].
+ * @property honorRetryAfter Whether the delay requested by a `Retry-After` response header, e.g. of a rate limiting
+ *                           proxy, is waited instead of the backoff. A call is not retried if the requested delay
+ *                           would outlast its deadline set through [withDeadline].
+ * @property maxRetryAfter Upper bound of the delay requested by a `Retry-After` header that is waited. Calls asked to
+ *                         wait longer are not retried.
+ */
+public data class RetryPolicy(
+    val maxAttempts: Int = 3,
+    val baseDelay: Duration = 200.milliseconds,
+    val maxDelay: Duration = 5.seconds,
+    val jitter: Double = 0.5,
+    val retryOnConnectionErrors: Boolean = true,
+    val retryOnStatuses: Set<HttpStatusCode> =
+        setOf(
+            HttpStatusCode.TooManyRequests,
+            HttpStatusCode.BadGateway,
+            HttpStatusCode.ServiceUnavailable,
+            HttpStatusCode.GatewayTimeout,
+        ),
+    val random: Random = Random.Default,
+    val honorRetryAfter: Boolean = true,
+    val maxRetryAfter: Duration = 1.minutes,
+) {
+    init {
+        require(maxAttempts > 0) { "Max attempts must be positive" }
+        require(!baseDelay.isNegative()) { "Base delay cannot be negative" }
+        require(maxDelay >= baseDelay) { "Max delay cannot be shorter than the base delay" }
+        require(jitter in 0.0..1.0) { "Jitter must be between 0.0 and 1.0" }
+        require(!maxRetryAfter.isNegative()) { "Max Retry-After cannot be negative" }
+    }
+
+    /**
+     * Returns the delay before the given retry, starting at `1` for the first one.
+     */
+    internal fun delayBefore(retry: Int): Duration {
+        val backoff = (baseDelay * 2.0.pow(retry - 1)).coerceAtMost(maxDelay)
+        return backoff * (1.0 - jitter * random.nextDouble())
+    }
+}
+
+/**
+ * Parses a `Retry-After` header value, either an amount of seconds or an HTTP date, into the delay it requests.
+ */
+internal fun parseRetryAfter(
+    value: String,
+    now: GMTDate = GMTDate(),
+): Duration? {
+    value.trim().toLongOrNull()?.let { seconds -> return seconds.coerceAtLeast(0).seconds }
+
+    val date = runCatching { value.fromHttpToGmtDate() }.getOrNull() ?: return null
+    return (date.timestamp - now.timestamp).coerceAtLeast(0).milliseconds
+}
+
+/**
+ * Returns the delay before retrying [response], or `null` if it must not be retried.
+ */
+private suspend fun RetryPolicy.delayBefore(
+    retry: Int,
+    response: HttpResponse,
+): Duration? {
+    val retryAfter =
+        response.headers[HttpHeaders.RetryAfter]
+            ?.takeIf { honorRetryAfter }
+            ?.let(::parseRetryAfter)
+            ?: return delayBefore(retry)
+    if (retryAfter > maxRetryAfter) return null
+
+    // no point in waiting if the call can't be completed in time anyway
+    val deadline = currentCoroutineContext()[DockerDeadline]?.deadline
+    if (deadline != null && retryAfter >= -deadline.elapsedNow()) return null
+
+    return retryAfter
+}
+
+private fun isReplayable(body: Any): Boolean = body !is StreamingContent && body !is OutgoingContent.ReadChannelContent
+
+@OptIn(InternalAPI::class)
+internal fun createRetryPlugin(policy: RetryPolicy) =
+    createClientPlugin("Retry") {
+        on(Send) { request ->
+            if (request.method !in IdempotentMethods || !isReplayable(request.body)) {
+                return@on proceed(request)
+            }
+
+            var attempt = 1
+            while (attempt < policy.maxAttempts) {
+                val call =
+                    try {
+                        proceed(request)
+                    } catch (e: IOException) {
+                        if (!policy.retryOnConnectionErrors) throw e
+                        null
+                    } catch (e: DockerResponseException) {
+                        if (e.statusCode !in policy.retryOnStatuses) throw e
+                        null
+                    }
+
+                val delay =
+                    if (call != null) {
+                        if (call.response.status !in policy.retryOnStatuses) return@on call
+                        val delay = policy.delayBefore(attempt, call.response) ?: return@on call
+
+                        // the response of a failed attempt is never read, releases its connection
+                        call.response.rawContent.cancel()
+                        delay
+                    } else {
+                        policy.delayBefore(attempt)
+                    }
+
+                delay(delay)
+                attempt++
+            }
+
+            proceed(request)
+        }
+    }
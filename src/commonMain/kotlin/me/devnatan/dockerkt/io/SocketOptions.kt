@@ -0,0 +1,41 @@
+package me.devnatan.dockerkt.io
+
+import kotlin.time.Duration
+
+/**
+ * Low-level options of the sockets used to connect to a Docker daemon. Buffer sizes also apply to Unix sockets,
+ * every other option only applies to TCP connections.
+ *
+ * Options that are not set keep the operating system defaults, except [tcpNoDelay] which is enabled by
+ * default. Only applied on the JVM for now.
+ *
+ * @property keepAlive Whether to send TCP keepalive probes on idle connections, so that half-open connections, e.g.
+ *                     dropped by a NAT gateway, are detected instead of hanging follow-mode streams forever.
+ * @property keepAliveIdle How long a connection must be idle before keepalive probes are sent.
+ * @property keepAliveInterval Time between keepalive probes.
+ * @property keepAliveCount Amount of unanswered keepalive probes after which the connection is dropped.
+ * @property tcpNoDelay Whether to disable Nagle's algorithm, so that small writes like keystrokes sent to an attached
+ *                      container or exec session are sent right away instead of being delayed to be batched.
+ * @property receiveBufferSize Size of the socket receive buffer (`SO_RCVBUF`), in bytes. Larger buffers can increase
+ *                             the throughput of log streams and image transfers on high-latency links.
+ * @property sendBufferSize Size of the socket send buffer (`SO_SNDBUF`), in bytes.
+ */
+public data class SocketOptions(
+    val keepAlive: Boolean? = null,
+    val keepAliveIdle: Duration? = null,
+    val keepAliveInterval: Duration? = null,
+    val keepAliveCount: Int? = null,
+    val tcpNoDelay: Boolean = true,
+    val receiveBufferSize: Int? = null,
+    val sendBufferSize: Int? = null,
+) {
+    init {
+        require(keepAliveIdle == null || keepAliveIdle.inWholeSeconds > 0) { "Keepalive idle must be at least 1 second" }
+        require(keepAliveInterval == null || keepAliveInterval.inWholeSeconds > 0) {
+            "Keepalive interval must be at least 1 second"
+        }
+        require(keepAliveCount == null || keepAliveCount > 0) { "Keepalive count must be positive" }
+        require(receiveBufferSize == null || receiveBufferSize > 0) { "Receive buffer size must be positive" }
+        require(sendBufferSize == null || sendBufferSize > 0) { "Send buffer size must be positive" }
+    }
+}
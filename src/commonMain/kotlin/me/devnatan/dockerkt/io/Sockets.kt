@@ -2,6 +2,7 @@
 
 package me.devnatan.dockerkt.io
 
+import kotlinx.io.IOException
 import kotlin.jvm.JvmName
 
 internal const val EncodedHostnameSuffix = ".socket"
@@ -10,6 +11,7 @@ internal const val MaxDnsLabelLength = 63
 internal const val DockerSocketPort = 2375
 internal const val UnixSocketPrefix = "unix://"
 internal const val HttpSocketPrefix = "tcp://"
+internal const val NamedPipeSocketPrefix = "npipe://"
 
 // unix:///var/run/docker.sock
 public const val DefaultDockerUnixSocket: String = "$UnixSocketPrefix/var/run/docker.sock"
@@ -17,7 +19,51 @@ public const val DefaultDockerUnixSocket: String = "$UnixSocketPrefix/var/run/do
 // tcp://localhost:2375
 public const val DefaultDockerHttpSocket: String = "${HttpSocketPrefix}localhost:$DockerSocketPort"
 
-internal fun isUnixSocket(input: String): Boolean = input.startsWith(UnixSocketPrefix)
+// npipe:////./pipe/docker_engine
+public const val DefaultDockerNamedPipe: String = "$NamedPipeSocketPrefix//./pipe/docker_engine"
+
+/**
+ * Thrown when the Unix socket of the Docker daemon can't be connected to, telling apart a daemon that is not running
+ * from a socket the current user is not allowed to use. Only thrown on the JVM for now.
+ *
+ * @property socketPath Path of the socket file.
+ * @property reason Why the socket can't be connected to.
+ */
+public class DockerSocketException internal constructor(
+    public val socketPath: String,
+    public val reason: Reason,
+    cause: Throwable? = null,
+) : IOException(cause) {
+    override val message: String
+        get() =
+            when (reason) {
+                Reason.NotFound -> "Docker socket not found at $socketPath, is the Docker daemon running?"
+                Reason.PermissionDenied ->
+                    "Permission denied on Docker socket $socketPath, add the current user to the docker group or " +
+                        "connect with a user allowed to use it"
+                Reason.ConnectionRefused ->
+                    "Connection refused on Docker socket $socketPath, the socket may be left over from a Docker daemon " +
+                        "that is no longer running"
+            }
+
+    public enum class Reason {
+        /**
+         * The socket file doesn't exist (`ENOENT`), usually because the Docker daemon is not running.
+         */
+        NotFound,
+
+        /**
+         * The current user is not allowed to write to the socket file (`EACCES`).
+         */
+        PermissionDenied,
+
+        /**
+         * Nothing is listening on the socket file (`ECONNREFUSED`), usually because it was left behind by a Docker
+         * daemon that is no longer running.
+         */
+        ConnectionRefused,
+    }
+}
 
 @OptIn(ExperimentalStdlibApi::class)
 internal fun encodeSocketPathHostname(socketPath: String): String =
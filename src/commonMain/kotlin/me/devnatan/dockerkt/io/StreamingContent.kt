@@ -0,0 +1,31 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.http.ContentType
+import io.ktor.http.content.OutgoingContent
+import io.ktor.utils.io.ByteWriteChannel
+import io.ktor.utils.io.writeFully
+
+private const val FillBufferSize = 8192
+
+/**
+ * Request body whose bytes are supplied by [fill] as it is sent, so that it never has to be entirely in memory.
+ *
+ * The size is not known upfront, so the body is sent with chunked transfer encoding.
+ *
+ * @param fill Fills the given buffer, returning the amount of bytes written to it, or `-1` once there's no more data.
+ */
+internal class StreamingContent(
+    override val contentType: ContentType,
+    private val fill: suspend (buffer: ByteArray) -> Int,
+) : OutgoingContent.WriteChannelContent() {
+    override suspend fun writeTo(channel: ByteWriteChannel) {
+        val buffer = ByteArray(FillBufferSize)
+        while (true) {
+            val filled = fill(buffer)
+            if (filled == -1) break
+
+            check(filled in 0..buffer.size) { "Filled $filled bytes into a buffer of ${buffer.size} bytes" }
+            channel.writeFully(buffer, 0, filled)
+        }
+    }
+}
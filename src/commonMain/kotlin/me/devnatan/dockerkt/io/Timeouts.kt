@@ -0,0 +1,27 @@
+package me.devnatan.dockerkt.io
+
+import kotlin.time.Duration
+
+/**
+ * Timeouts of the HTTP calls made to a Docker daemon, where [Duration.ZERO] means no timeout.
+ *
+ * None is set by default, since calls like following container logs or `/events` are meant to stay open forever.
+ * Prefer [me.devnatan.dockerkt.withDeadline] to bound specific calls instead of setting a [total] timeout for all of
+ * them.
+ *
+ * @property connect Maximum time to establish a connection to the Docker daemon, including the TLS handshake.
+ * @property readIdle Maximum time without receiving any data while waiting for or reading a response. Applies to
+ *                    streams as well, e.g. the logs of a quiet container.
+ * @property total Maximum time of a whole call, from connecting to reading the whole response, streams included.
+ */
+public data class Timeouts(
+    val connect: Duration = Duration.ZERO,
+    val readIdle: Duration = Duration.ZERO,
+    val total: Duration = Duration.ZERO,
+) {
+    init {
+        require(!connect.isNegative()) { "Connect timeout cannot be negative" }
+        require(!readIdle.isNegative()) { "Read idle timeout cannot be negative" }
+        require(!total.isNegative()) { "Total timeout cannot be negative" }
+    }
+}
@@ -0,0 +1,81 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.client.request.header
+import kotlin.random.Random
+
+private const val TraceParentHeader = "traceparent"
+private const val TraceStateHeader = "tracestate"
+private const val TraceParentVersion = "00"
+private val TraceIdRegex = Regex("[0-9a-f]{32}")
+private val SpanIdRegex = Regex("[0-9a-f]{16}")
+
+/**
+ * A [W3C Trace Context](https://www.w3.org/TR/trace-context/) that Docker API calls are part of.
+ *
+ * @property traceId Lowercase hex identifier of the whole trace, 32 characters long.
+ * @property spanId Lowercase hex identifier of the span the calls are made from, 16 characters long.
+ * @property sampled Whether the trace is being recorded.
+ * @property traceState Vendor-specific `tracestate` value propagated as is, if any.
+ */
+public data class TraceContext(
+    val traceId: String,
+    val spanId: String,
+    val sampled: Boolean = true,
+    val traceState: String? = null,
+) {
+    init {
+        require(traceId.matches(TraceIdRegex) && traceId.any { it != '0' }) { "Invalid trace id: $traceId" }
+        require(spanId.matches(SpanIdRegex) && spanId.any { it != '0' }) { "Invalid span id: $spanId" }
+    }
+
+    public companion object {
+        /**
+         * Parses a `traceparent` header value, returning `null` if it's not a valid one.
+         *
+         * @param traceParent The `traceparent` header value.
+         * @param traceState The `tracestate` header value, if any.
+         */
+        public fun parse(
+            traceParent: String,
+            traceState: String? = null,
+        ): TraceContext? {
+            val parts = traceParent.trim().split('-')
+            // version 00 has exactly four fields, unlike future versions that may append more
+            if (parts.size != 4 || parts[0] != TraceParentVersion || parts[3].length != 2) return null
+
+            val flags = parts[3].toIntOrNull(radix = 16) ?: return null
+            return runCatching { TraceContext(parts[1], parts[2], sampled = flags and 1 == 1, traceState) }.getOrNull()
+        }
+    }
+}
+
+/**
+ * Propagates the trace context through `traceparent` and `tracestate` headers on every Docker API call, so that they
+ * show up as part of distributed traces.
+ *
+ * The context returned by [parentContext] is forwarded unchanged, so the Docker daemon sees the caller span as its
+ * parent. If there's none, a new trace is started for that call alone.
+ *
+ * @property parentContext Provides the context of the span calls are made from, e.g. the current OpenTelemetry span.
+ */
+public class TracePropagation(
+    public val parentContext: () -> TraceContext? = { null },
+)
+
+private fun randomHex(bytes: Int): String =
+    Random.nextBytes(bytes).joinToString("") { byte -> (byte.toInt() and 0xff).toString(16).padStart(2, '0') }
+
+internal fun createTracePropagationPlugin(tracing: TracePropagation) =
+    createClientPlugin("TracePropagation") {
+        onRequest { request, _ ->
+            // trace headers set explicitly on the request take precedence
+            if (request.headers.contains(TraceParentHeader)) return@onRequest
+
+            val context = tracing.parentContext() ?: TraceContext(traceId = randomHex(16), spanId = randomHex(8))
+
+            val flags = if (context.sampled) "01" else "00"
+            request.header(TraceParentHeader, "$TraceParentVersion-${context.traceId}-${context.spanId}-$flags")
+            context.traceState?.let { state -> request.header(TraceStateHeader, state) }
+        }
+    }
@@ -0,0 +1,221 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.call.wrapWithContent
+import io.ktor.client.plugins.api.Send
+import io.ktor.client.plugins.api.createClientPlugin
+import io.ktor.client.request.HttpRequest
+import io.ktor.client.statement.HttpResponse
+import io.ktor.http.Headers
+import io.ktor.http.HttpHeaders
+import io.ktor.http.content.ByteArrayContent
+import io.ktor.http.content.OutgoingContent
+import io.ktor.http.content.TextContent
+import io.ktor.http.isWebsocket
+import io.ktor.util.encodeBase64
+import io.ktor.utils.io.InternalAPI
+import io.ktor.utils.io.readAvailable
+import io.ktor.utils.io.writeFully
+import io.ktor.utils.io.writer
+import kotlinx.coroutines.Dispatchers
+import kotlinx.coroutines.IO
+import kotlinx.coroutines.NonCancellable
+import kotlinx.coroutines.sync.Mutex
+import kotlinx.coroutines.sync.withLock
+import kotlinx.coroutines.withContext
+import kotlinx.io.Buffer
+import kotlinx.io.buffered
+import kotlinx.io.files.Path
+import kotlinx.io.files.SystemFileSystem
+import kotlinx.io.readByteArray
+import kotlin.time.Clock
+import kotlin.time.ExperimentalTime
+
+private const val RedactedValue = "<redacted>"
+
+/**
+ * Headers that carry credentials and are redacted by default.
+ */
+public val DefaultRedactedHeaders: Set<String> =
+    setOf(
+        HttpHeaders.Authorization,
+        HttpHeaders.ProxyAuthorization,
+        HttpHeaders.Cookie,
+        HttpHeaders.SetCookie,
+        "X-Registry-Auth",
+        "X-Registry-Config",
+    )
+
+// JSON fields holding registry credentials, e.g. in the body of POST /auth
+private val CredentialFieldRegex =
+    Regex("\"(password|auth|identitytoken|registrytoken)\"\\s*:\\s*\"(?:[^\"\\\\]|\\\\.)*\"", RegexOption.IGNORE_CASE)
+
+/**
+ * Writes the requests and responses of a client to a text file, with credentials redacted, to diagnose protocol
+ * disagreements with the Docker daemon like unexpected fields.
+ *
+ * Response bodies are dumped as they are read, so streaming calls are dumped as well, up to [maxBodySize] bytes.
+ * Once the file reaches [maxFileSize] it's rotated: `dump.log` is renamed to `dump.log.1`, `dump.log.1` to
+ * `dump.log.2` and so on, keeping at most [maxFiles] files.
+ *
+ * @property path Path of the dump file on the local filesystem.
+ * @property maxFileSize Size, in bytes, the dump file can reach before being rotated.
+ * @property maxFiles Maximum amount of dump files kept, including the current one.
+ * @property maxBodySize Maximum amount of bytes dumped of each request and response body.
+ * @property redactedHeaders Names of the headers whose values are redacted.
+ */
+public data class TrafficDump(
+    val path: String,
+    val maxFileSize: Long = 10L * 1024 * 1024,
+    val maxFiles: Int = 5,
+    val maxBodySize: Long = 64L * 1024,
+    val redactedHeaders: Set<String> = DefaultRedactedHeaders,
+) {
+    init {
+        require(maxFileSize > 0) { "Max file size must be positive" }
+        require(maxFiles > 0) { "Max files must be positive" }
+        require(maxBodySize >= 0) { "Max body size cannot be negative" }
+    }
+}
+
+private class TrafficDumpWriter(
+    private val dump: TrafficDump,
+) {
+    private val mutex = Mutex()
+    private val path = Path(dump.path)
+    private var nextId = 1L
+
+    suspend fun nextId(): Long = mutex.withLock { nextId++ }
+
+    // the file is written on the IO dispatcher, as the calls being dumped can run on any dispatcher
+    suspend fun write(record: String) =
+        mutex.withLock {
+            withContext(Dispatchers.IO) {
+                val bytes = record.encodeToByteArray()
+                val size = SystemFileSystem.metadataOrNull(path)?.size ?: 0
+                if (size > 0 && size + bytes.size > dump.maxFileSize) {
+                    rotate()
+                }
+
+                SystemFileSystem.sink(path, append = true).buffered().use { sink -> sink.write(bytes) }
+            }
+        }
+
+    private fun rotate() {
+        if (dump.maxFiles == 1) {
+            SystemFileSystem.delete(path)
+            return
+        }
+
+        SystemFileSystem.delete(Path("${dump.path}.${dump.maxFiles - 1}"), mustExist = false)
+        for (index in dump.maxFiles - 2 downTo 0) {
+            val source = if (index == 0) path else Path("${dump.path}.$index")
+            if (SystemFileSystem.exists(source)) {
+                SystemFileSystem.atomicMove(source, Path("${dump.path}.${index + 1}"))
+            }
+        }
+    }
+}
+
+private fun TrafficDump.redact(headers: Headers): String =
+    buildString {
+        headers.forEach { name, values ->
+            val redacted = redactedHeaders.any { header -> header.equals(name, ignoreCase = true) }
+            values.forEach { value -> append("$name: ${if (redacted) RedactedValue else value}\n") }
+        }
+    }
+
+private fun redact(body: String): String =
+    body.replace(CredentialFieldRegex) { match -> "\"${match.groupValues[1]}\":\"$RedactedValue\"" }
+
+/**
+ * Describes a body that may be binary, e.g. an image tarball or a multiplexed log stream, which is dumped base64
+ * encoded instead of as text.
+ */
+private fun describeBody(body: ByteArray): String {
+    // a truncated body may end in the middle of a multibyte character
+    val text =
+        (0..minOf(3, body.size)).firstNotNullOfOrNull { dropped ->
+            runCatching { body.decodeToString(0, body.size - dropped, throwOnInvalidSequence = true) }.getOrNull()
+        }
+    if (text == null || text.any { char -> char < ' ' && char != '\n' && char != '\r' && char != '\t' }) {
+        return "<binary body, base64 encoded> ${body.encodeBase64()}"
+    }
+
+    return redact(text)
+}
+
+private fun TrafficDump.describe(content: OutgoingContent): String? {
+    val body =
+        when (content) {
+            is TextContent -> content.text.encodeToByteArray()
+            is ByteArrayContent -> content.bytes()
+            is OutgoingContent.NoContent -> return null
+            else -> return "<streamed body>"
+        }
+
+    return describeBody(body.copyOf(minOf(body.size.toLong(), maxBodySize).toInt()))
+}
+
+@OptIn(ExperimentalTime::class)
+private fun TrafficDump.describe(
+    id: Long,
+    request: HttpRequest,
+    response: HttpResponse,
+): String =
+    buildString {
+        append(">>> #$id ${Clock.System.now()} ${request.method.value} ${request.url}\n")
+        append(redact(request.headers))
+        request.content.contentType?.let { contentType -> append("${HttpHeaders.ContentType}: $contentType\n") }
+        describe(request.content)?.let { body -> append("\n$body\n") }
+
+        append("<<< #$id ${response.version} ${response.status}\n")
+        append(redact(response.headers))
+        append("\n")
+    }
+
+@OptIn(InternalAPI::class, ExperimentalTime::class)
+internal fun createTrafficDumpPlugin(dump: TrafficDump) =
+    createClientPlugin("TrafficDump") {
+        val dumpWriter = TrafficDumpWriter(dump)
+
+        on(Send) { request ->
+            val call = proceed(request)
+            val id = dumpWriter.nextId()
+            dumpWriter.write(dump.describe(id, call.request, call.response))
+
+            // the connection is taken over by the WebSocket session once upgraded
+            if (call.request.url.protocol.isWebsocket()) return@on call
+
+            val original = call.response.rawContent
+            val teed =
+                client
+                    .writer {
+                        val captured = Buffer()
+                        val buffer = ByteArray(8192)
+                        var total = 0L
+                        try {
+                            while (true) {
+                                val read = original.readAvailable(buffer, 0, buffer.size)
+                                if (read == -1) break
+
+                                channel.writeFully(buffer, 0, read)
+                                channel.flush()
+
+                                val capturable = (dump.maxBodySize - captured.size).coerceAtMost(read.toLong())
+                                if (capturable > 0) captured.write(buffer, 0, capturable.toInt())
+                                total += read
+                            }
+                        } finally {
+                            withContext(NonCancellable) {
+                                val truncated = if (total > captured.size) " (truncated)" else ""
+                                dumpWriter.write(
+                                    "<<< #$id ${Clock.System.now()} body of $total bytes$truncated\n" +
+                                        describeBody(captured.readByteArray()) + "\n\n",
+                                )
+                            }
+                        }
+                    }.channel
+
+            call.wrapWithContent(teed)
+        }
+    }
@@ -0,0 +1,94 @@
+package me.devnatan.dockerkt.models
+
+import kotlinx.serialization.json.Json
+import kotlinx.serialization.json.buildJsonObject
+import kotlinx.serialization.json.put
+import kotlin.io.encoding.Base64
+
+/**
+ * Credentials a Docker registry is authenticated with, sent to the Docker daemon in the `X-Registry-Auth` header.
+ *
+ * Secrets are given in arrays rather than strings so that they can be wiped, which happens as soon as they're encoded
+ * in the header of a call. An instance can then only authenticate a single call, reusing it throws an
+ * [IllegalStateException].
+ *
+ * Wiping only clears the arrays given: encoding the header copies the secrets into strings, the header itself being one,
+ * which can't be wiped and remain in memory until garbage collected.
+ */
+public sealed class RegistryAuth {
+    internal var wiped: Boolean = false
+
+    /**
+     * Address of the registry, e.g. `registry.example.com`, or `null` to let the Docker daemon infer it from the image.
+     */
+    public abstract val serverAddress: String?
+
+    /**
+     * Overwrites the secrets of these credentials with zeros so that they don't linger in memory.
+     */
+    public abstract fun wipe()
+
+    /**
+     * Credentials made of a username and a password.
+     *
+     * @property username Username to authenticate with.
+     * @property password Password to authenticate with.
+     */
+    public class Password(
+        public val username: String,
+        public val password: CharArray,
+        override val serverAddress: String? = null,
+    ) : RegistryAuth() {
+        override fun wipe() {
+            password.fill(Char.MIN_VALUE)
+            wiped = true
+        }
+    }
+
+    /**
+     * Credentials made of an identity token, as returned by the Docker daemon when logging in to a registry.
+     *
+     * @property identityToken Token to authenticate with.
+     */
+    public class IdentityToken(
+        public val identityToken: CharArray,
+        override val serverAddress: String? = null,
+    ) : RegistryAuth() {
+        override fun wipe() {
+            identityToken.fill(Char.MIN_VALUE)
+            wiped = true
+        }
+    }
+}
+
+/**
+ * Encodes these credentials as the value of the `X-Registry-Auth` header, a base64url encoded JSON object, and wipes
+ * them afterwards. The strings built along the way still hold the secrets, see [RegistryAuth].
+ *
+ * @throws IllegalStateException If these credentials were already wiped.
+ */
+internal fun RegistryAuth.encodeAndWipe(): String {
+    check(!wiped) { "Registry credentials were already used and wiped, create new ones for every call" }
+    return try {
+        val config =
+            buildJsonObject {
+                when (this@encodeAndWipe) {
+                    is RegistryAuth.Password -> {
+                        put("username", username)
+                        put("password", password.concatToString())
+                    }
+                    is RegistryAuth.IdentityToken -> put("identitytoken", identityToken.concatToString())
+                }
+                serverAddress?.let { address -> put("serveraddress", address) }
+            }
+
+        val encoded = Json.encodeToString(config).encodeToByteArray()
+        try {
+            Base64.UrlSafe.encode(encoded)
+        } finally {
+            encoded.fill(0)
+        }
+    } finally {
+        wipe()
+    }
+}
@@ -0,0 +1,22 @@
+package me.devnatan.dockerkt.models.container
+
+import kotlin.jvm.JvmOverloads
+
+/**
+ * Container attach endpoint options.
+ *
+ * @property logs Replays the output the container produced before attaching.
+ * @property stream Streams the output produced after attaching.
+ * @property stdin Attaches to `stdin`, so that input can be sent to the container.
+ * @property stdout Attaches to `stdout`.
+ * @property stderr Attaches to `stderr`.
+ */
+public class ContainerAttachOptions
+    @JvmOverloads
+    constructor(
+        public var logs: Boolean? = null,
+        public var stream: Boolean? = true,
+        public var stdin: Boolean? = true,
+        public var stdout: Boolean? = true,
+        public var stderr: Boolean? = true,
+    )
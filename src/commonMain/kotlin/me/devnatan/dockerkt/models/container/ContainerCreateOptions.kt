@@ -42,6 +42,7 @@ public data class ContainerCreateOptions(
     @SerialName("HostConfig") public var hostConfig: HostConfig? = null,
     @SerialName("NetworkingConfig") public var networkingConfig: NetworkingConfig? = null,
     @SerialName("Tty") public var tty: Boolean? = null,
+    @SerialName("OpenStdin") public var openStdin: Boolean? = null,
 )
 
 public fun ContainerCreateOptions.exposedPort(port: UShort) {
@@ -0,0 +1,18 @@
+package me.devnatan.dockerkt.models.image
+
+/**
+ * Options for importing an image from a tarball.
+ *
+ * @property repository Repository name given to the imported image.
+ * @property tag Tag given to the imported image.
+ * @property message Commit message for the imported image.
+ * @property changes Dockerfile instructions to apply while importing the image (e.g. `ENV DEBUG=true`).
+ * @property platform Platform in the format os[/arch[/variant]].
+ */
+public data class ImageImportOptions(
+    public var repository: String? = null,
+    public var tag: String? = null,
+    public var message: String? = null,
+    public var changes: List<String> = emptyList(),
+    public var platform: String? = null,
+)
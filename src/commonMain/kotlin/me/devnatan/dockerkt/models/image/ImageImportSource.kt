@@ -0,0 +1,24 @@
+package me.devnatan.dockerkt.models.image
+
+/**
+ * Where the root filesystem tarball of an image import comes from.
+ */
+public sealed class ImageImportSource {
+    /**
+     * A tarball on the local filesystem, streamed to the Docker daemon as the request body.
+     *
+     * @property path Path to the tarball on the local filesystem.
+     */
+    public data class File(
+        val path: String,
+    ) : ImageImportSource()
+
+    /**
+     * A tarball the Docker daemon will download by itself.
+     *
+     * @property url URL of the tarball.
+     */
+    public data class Url(
+        val url: String,
+    ) : ImageImportSource()
+}
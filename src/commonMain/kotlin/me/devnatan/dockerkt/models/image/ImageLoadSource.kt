@@ -0,0 +1,26 @@
+package me.devnatan.dockerkt.models.image
+
+/**
+ * Where the tarball of an image load comes from, like the one created by `docker save`.
+ */
+public sealed class ImageLoadSource {
+    /**
+     * A tarball on the local filesystem, streamed to the Docker daemon as the request body.
+     *
+     * @property path Path to the tarball on the local filesystem.
+     */
+    public data class File(
+        val path: String,
+    ) : ImageLoadSource()
+
+    /**
+     * A tarball supplied chunk by chunk as it's sent to the Docker daemon, e.g. read from an `InputStream`, so that
+     * tarballs of many gigabytes are never buffered in memory.
+     *
+     * @property fill Fills the given buffer with the next chunk of the tarball, returning the amount of bytes written
+     *                to it, or `-1` once the whole tarball has been supplied.
+     */
+    public class Stream(
+        public val fill: suspend (buffer: ByteArray) -> Int,
+    ) : ImageLoadSource()
+}
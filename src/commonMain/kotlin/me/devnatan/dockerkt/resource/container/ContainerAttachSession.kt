@@ -0,0 +1,50 @@
+package me.devnatan.dockerkt.resource.container
+
+import io.ktor.client.plugins.websocket.DefaultClientWebSocketSession
+import io.ktor.websocket.close
+import io.ktor.websocket.readBytes
+import kotlinx.coroutines.flow.Flow
+import kotlinx.coroutines.flow.mapNotNull
+import kotlinx.coroutines.flow.receiveAsFlow
+import io.ktor.websocket.Frame as WebSocketFrame
+
+/**
+ * A WebSocket connection attached to a container, opened through [ContainerResource.attachWebSocket].
+ *
+ * The output is sent raw, not multiplexed, so `stdout` and `stderr` can't be told apart.
+ */
+public class ContainerAttachSession internal constructor(
+    private val session: DefaultClientWebSocketSession,
+) {
+    /**
+     * Output of the container, as the chunks sent by the Docker daemon. Completes when the connection is closed.
+     */
+    public val output: Flow<ByteArray> =
+        session.incoming.receiveAsFlow().mapNotNull { frame ->
+            when (frame) {
+                is WebSocketFrame.Binary, is WebSocketFrame.Text -> frame.readBytes()
+                else -> null
+            }
+        }
+
+    /**
+     * Sends [data] to the container `stdin`.
+     */
+    public suspend fun send(data: ByteArray) {
+        session.send(WebSocketFrame.Binary(fin = true, data))
+    }
+
+    /**
+     * Sends [text] to the container `stdin`.
+     */
+    public suspend fun send(text: String) {
+        session.send(WebSocketFrame.Text(text))
+    }
+
+    /**
+     * Closes the connection, detaching from the container.
+     */
+    public suspend fun close() {
+        session.close()
+    }
+}
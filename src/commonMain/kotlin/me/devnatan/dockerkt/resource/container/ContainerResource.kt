@@ -2,6 +2,7 @@ package me.devnatan.dockerkt.resource.container
 
 import io.ktor.client.HttpClient
 import io.ktor.client.call.body
+import io.ktor.client.plugins.websocket.webSocket
 import io.ktor.client.request.delete
 import io.ktor.client.request.get
 import io.ktor.client.request.parameter
@@ -14,6 +15,7 @@ import io.ktor.client.statement.bodyAsChannel
 import io.ktor.client.statement.readRawBytes
 import io.ktor.http.ContentType
 import io.ktor.http.HttpStatusCode
+import io.ktor.http.contentLength
 import io.ktor.http.content.ByteArrayContent
 import io.ktor.http.contentType
 import io.ktor.util.decodeBase64Bytes
@@ -25,7 +27,9 @@ import kotlinx.coroutines.flow.flow
 import kotlinx.io.files.Path
 import kotlinx.serialization.json.Json
 import me.devnatan.dockerkt.DockerResponseException
+import me.devnatan.dockerkt.DockerWarning
 import me.devnatan.dockerkt.io.FileSystemUtils
+import me.devnatan.dockerkt.io.RequestIdKey
 import me.devnatan.dockerkt.io.TarEntry
 import me.devnatan.dockerkt.io.TarOperations
 import me.devnatan.dockerkt.io.TarUtils
@@ -38,6 +42,7 @@ import me.devnatan.dockerkt.models.ResizeTTYOptions
 import me.devnatan.dockerkt.models.Stream
 import me.devnatan.dockerkt.models.container.Container
 import me.devnatan.dockerkt.models.container.ContainerArchiveInfo
+import me.devnatan.dockerkt.models.container.ContainerAttachOptions
 import me.devnatan.dockerkt.models.container.ContainerCopyOptions
 import me.devnatan.dockerkt.models.container.ContainerCopyResult
 import me.devnatan.dockerkt.models.container.ContainerCreateOptions
@@ -61,6 +66,7 @@ private const val BasePath = "/containers"
 public class ContainerResource internal constructor(
     private val httpClient: HttpClient,
     private val json: Json,
+    private val warningHandler: (DockerWarning) -> Unit,
 ) {
     /**
      * Returns a list of all containers.
@@ -87,7 +93,7 @@ public class ContainerResource internal constructor(
     public suspend fun create(options: ContainerCreateOptions): String {
         requireNotNull(options.image) { "Container image is required" }
 
-        val result =
+        val response =
             requestCatching(
                 HttpStatusCode.NotFound to { exception -> ImageNotFoundException(exception, options.image.orEmpty()) },
                 HttpStatusCode.Conflict to { exception ->
@@ -101,9 +107,13 @@ public class ContainerResource internal constructor(
                     parameter("name", options.name)
                     setBody(options)
                 }
-            }.body<ContainerCreateResult>()
+            }
+        val result = response.body<ContainerCreateResult>()
 
-        result.warnings.forEach { warn -> println("Warning from Docker API: $warn") }
+        val requestId = response.call.attributes.getOrNull(RequestIdKey)
+        result.warnings.forEach { warning ->
+            warningHandler(DockerWarning(message = warning, method = "POST", path = "$BasePath/create", requestId = requestId))
+        }
         return result.id
     }
 
@@ -315,6 +325,39 @@ public class ContainerResource internal constructor(
                 }
         }
 
+    /**
+     * Attaches to a container over a WebSocket connection, which is closed once [block] returns.
+     *
+     * Unlike [attach], input can be sent to the container through [ContainerAttachSession.send] while its output is
+     * read from [ContainerAttachSession.output].
+     *
+     * @param container Container id or name.
+     * @param options Which streams to attach to. See [ContainerAttachOptions].
+     * @param block Interacts with the container through the attached session.
+     * @throws ContainerNotFoundException If the container is not found.
+     */
+    public suspend fun attachWebSocket(
+        container: String,
+        options: ContainerAttachOptions = ContainerAttachOptions(),
+        block: suspend ContainerAttachSession.() -> Unit,
+    ): Unit =
+        requestCatching(
+            HttpStatusCode.NotFound to { cause -> ContainerNotFoundException(cause, container) },
+        ) {
+            httpClient.webSocket(
+                urlString = "$BasePath/$container/attach/ws",
+                request = {
+                    parameter("logs", options.logs)
+                    parameter("stream", options.stream)
+                    parameter("stdin", options.stdin)
+                    parameter("stdout", options.stdout)
+                    parameter("stderr", options.stderr)
+                },
+            ) {
+                ContainerAttachSession(this).block()
+            }
+        }
+
     /**
      * Get resource usage statistics for a container.
      *
@@ -508,6 +551,37 @@ public class ContainerResource internal constructor(
         }
     }
 
+    /**
+     * Export the contents of a container as a tarball.
+     *
+     * The response is streamed straight to [destinationPath] so the whole root filesystem of the
+     * container never has to be held in memory.
+     *
+     * @param container Container id or name.
+     * @param destinationPath Path on the local filesystem where the tarball will be written.
+     * @param onProgress Called with the total amount of bytes received so far as the export progresses, and the size
+     *                   of the response declared by the daemon, or `null` if it's not known upfront.
+     * @return The total amount of bytes written to [destinationPath].
+     * @throws ContainerNotFoundException If the container is not found.
+     */
+    public suspend fun export(
+        container: String,
+        destinationPath: String,
+        onProgress: ((bytesReceived: Long, totalBytes: Long?) -> Unit)? = null,
+    ): Long =
+        requestCatching(
+            HttpStatusCode.NotFound to { cause -> ContainerNotFoundException(cause, container) },
+        ) {
+            httpClient
+                .prepareGet("$BasePath/$container/export")
+                .execute { response ->
+                    val totalBytes = response.contentLength()
+                    FileSystemUtils.writeChannel(Path(destinationPath), response.bodyAsChannel()) { bytesReceived ->
+                        onProgress?.invoke(bytesReceived, totalBytes)
+                    }
+                }
+        }
+
     /**
      * Copy files or folders from a container to the local filesystem.
      *
@@ -16,3 +16,13 @@ public class ImagePullDeniedException internal constructor(
     public val image: String,
     public override val message: String,
 ) : ImageException(cause)
+
+public class ImageImportException internal constructor(
+    cause: Throwable?,
+    public override val message: String,
+) : ImageException(cause)
+
+public class ImageLoadException internal constructor(
+    cause: Throwable?,
+    public override val message: String,
+) : ImageException(cause)
@@ -2,28 +2,43 @@ package me.devnatan.dockerkt.resource.image
 
 import io.ktor.client.HttpClient
 import io.ktor.client.call.body
+import io.ktor.client.plugins.onUpload
 import io.ktor.client.request.delete
 import io.ktor.client.request.get
 import io.ktor.client.request.header
 import io.ktor.client.request.parameter
 import io.ktor.client.request.post
+import io.ktor.client.request.prepareGet
 import io.ktor.client.request.preparePost
 import io.ktor.client.request.setBody
+import io.ktor.client.statement.bodyAsChannel
 import io.ktor.http.ContentType
 import io.ktor.http.HttpStatusCode
-import io.ktor.http.contentType
+import io.ktor.http.contentLength
 import io.ktor.utils.io.ByteReadChannel
 import io.ktor.utils.io.readUTF8Line
 import kotlinx.coroutines.flow.Flow
 import kotlinx.coroutines.flow.channelFlow
+import kotlinx.io.files.Path
 import kotlinx.serialization.json.Json
+import kotlinx.serialization.json.jsonObject
+import kotlinx.serialization.json.jsonPrimitive
+import me.devnatan.dockerkt.io.FileContent
+import me.devnatan.dockerkt.io.FileSystemUtils
+import me.devnatan.dockerkt.io.StreamingContent
 import me.devnatan.dockerkt.io.requestCatching
+import me.devnatan.dockerkt.models.RegistryAuth
+import me.devnatan.dockerkt.models.encodeAndWipe
 import me.devnatan.dockerkt.models.image.Image
 import me.devnatan.dockerkt.models.image.ImageBuildOptions
+import me.devnatan.dockerkt.models.image.ImageImportOptions
+import me.devnatan.dockerkt.models.image.ImageImportSource
+import me.devnatan.dockerkt.models.image.ImageLoadSource
 import me.devnatan.dockerkt.models.image.ImagePull
 import me.devnatan.dockerkt.models.image.ImageSummary
 
 private const val BasePath = "/images"
+private val LoadedImageRegex = Regex("Loaded image(?: ID)?: (.+)")
 
 public class ImageResource internal constructor(
     private val httpClient: HttpClient,
@@ -35,8 +50,24 @@ public class ImageResource internal constructor(
 
     public suspend fun list(): List<ImageSummary> = httpClient.get("$BasePath/json").body()
 
-    public fun pull(image: String): Flow<ImagePull> =
-        channelFlow {
+    public fun pull(image: String): Flow<ImagePull> = pull(image, auth = null)
+
+    /**
+     * Pulls an image from a registry, authenticating with [auth].
+     *
+     * @param image Name of the image to pull.
+     * @param auth Credentials the registry is authenticated with, or `null` to pull anonymously. They're encoded and
+     *             wiped by this call, so every collection of the returned flow authenticates with them, see
+     *             [RegistryAuth].
+     * @throws IllegalStateException If [auth] was already used by another call.
+     */
+    public fun pull(
+        image: String,
+        auth: RegistryAuth?,
+    ): Flow<ImagePull> {
+        // encoded before the flow is built since it wipes the credentials, which would be blank on a second collection
+        val authHeader = auth?.encodeAndWipe()
+        return channelFlow {
             requestCatching(
                 HttpStatusCode.NotFound to { exception ->
                     val errorMessage = exception.message.orEmpty().lowercase()
@@ -50,6 +81,7 @@ public class ImageResource internal constructor(
                 httpClient
                     .preparePost("$BasePath/create") {
                         parameter("fromImage", image)
+                        header("X-Registry-Auth", authHeader)
                     }.execute { response ->
                         val channel = response.body<ByteReadChannel>()
                         while (true) {
@@ -59,6 +91,183 @@ public class ImageResource internal constructor(
                     }
             }
         }
+    }
+
+    /**
+     * Imports an image from a root filesystem tarball.
+     *
+     * Local tarballs are streamed to the Docker daemon as they are read, so they never have to fit in memory.
+     *
+     * @param source Where the tarball comes from. See [ImageImportSource].
+     * @param options Options to customize the imported image.
+     * @return The id of the imported image.
+     * @throws ImageImportException If the Docker daemon fails to import the image.
+     */
+    public suspend fun import(
+        source: ImageImportSource,
+        options: ImageImportOptions = ImageImportOptions(),
+    ): String {
+        if (source is ImageImportSource.File) {
+            require(FileSystemUtils.exists(Path(source.path))) { "Source file not found: ${source.path}" }
+        }
+
+        return requestCatching {
+            httpClient
+                .preparePost("$BasePath/create") {
+                    parameter("repo", options.repository)
+                    parameter("tag", options.tag)
+                    parameter("message", options.message)
+                    parameter("platform", options.platform)
+                    options.changes.forEach { change -> parameter("changes", change) }
+
+                    when (source) {
+                        is ImageImportSource.File -> {
+                            parameter("fromSrc", "-")
+                            setBody(FileContent(Path(source.path), TAR_CONTENT_TYPE))
+                        }
+
+                        is ImageImportSource.Url -> {
+                            parameter("fromSrc", source.url)
+                        }
+                    }
+                }.execute { response ->
+                    val channel = response.body<ByteReadChannel>()
+                    var imageId: String? = null
+                    while (true) {
+                        val line = channel.readUTF8Line() ?: break
+                        if (line.isBlank()) continue
+
+                        val message = json.parseToJsonElement(line).jsonObject
+                        message["error"]?.let { error ->
+                            throw ImageImportException(null, error.jsonPrimitive.content)
+                        }
+
+                        // the last status line holds the id of the imported image
+                        val status = message["status"]?.jsonPrimitive?.content
+                        if (status != null && status.startsWith("sha256:")) {
+                            imageId = status
+                        }
+                    }
+
+                    imageId ?: throw ImageImportException(null, "Docker daemon did not report the imported image id")
+                }
+        }
+    }
+
+    /**
+     * Loads the images and tags of a tarball created by `docker save` or [save].
+     *
+     * The tarball is streamed to the Docker daemon as it's read, so it never has to fit in memory.
+     *
+     * @param source Where the tarball comes from. See [ImageLoadSource].
+     * @param onProgress Called with the total amount of bytes sent so far as the upload progresses, and the size of
+     *                   the tarball if it's known upfront.
+     * @return The names of the loaded images, or their ids for the untagged ones.
+     * @throws ImageLoadException If the Docker daemon fails to load the tarball.
+     */
+    public suspend fun load(
+        source: ImageLoadSource,
+        onProgress: ((bytesSent: Long, totalBytes: Long?) -> Unit)? = null,
+    ): List<String> {
+        if (source is ImageLoadSource.File) {
+            require(FileSystemUtils.exists(Path(source.path))) { "Source file not found: ${source.path}" }
+        }
+
+        return requestCatching {
+            httpClient
+                .preparePost("$BasePath/load") {
+                    parameter("quiet", false)
+                    onProgress?.let { listener -> onUpload { bytesSent, totalBytes -> listener(bytesSent, totalBytes) } }
+                    setBody(
+                        when (source) {
+                            is ImageLoadSource.File -> FileContent(Path(source.path), TAR_CONTENT_TYPE)
+                            is ImageLoadSource.Stream -> StreamingContent(TAR_CONTENT_TYPE, source.fill)
+                        },
+                    )
+                }.execute { response ->
+                    val channel = response.body<ByteReadChannel>()
+                    val loaded = mutableListOf<String>()
+                    while (true) {
+                        val line = channel.readUTF8Line() ?: break
+                        if (line.isBlank()) continue
+
+                        val message = json.parseToJsonElement(line).jsonObject
+                        message["error"]?.let { error ->
+                            throw ImageLoadException(null, error.jsonPrimitive.content)
+                        }
+
+                        // e.g. "Loaded image: busybox:latest" or "Loaded image ID: sha256:..."
+                        val stream = message["stream"]?.jsonPrimitive?.content?.trim() ?: continue
+                        LoadedImageRegex.matchEntire(stream)?.let { match -> loaded += match.groupValues[1] }
+                    }
+                    loaded
+                }
+        }
+    }
+
+    /**
+     * Export an image, with all its layers, tags and metadata, as a tarball that can be loaded back with [load].
+     *
+     * The response is streamed straight to [destinationPath] so the image never has to be held in memory.
+     *
+     * @param image Image name or id.
+     * @param destinationPath Path on the local filesystem where the tarball will be written.
+     * @param onProgress Called with the total amount of bytes received so far as the export progresses, and the size
+     *                   of the response declared by the daemon, or `null` if it's not known upfront.
+     * @return The total amount of bytes written to [destinationPath].
+     * @throws ImageNotFoundException If the image is not found.
+     */
+    public suspend fun save(
+        image: String,
+        destinationPath: String,
+        onProgress: ((bytesReceived: Long, totalBytes: Long?) -> Unit)? = null,
+    ): Long =
+        requestCatching(
+            HttpStatusCode.NotFound to { exception -> ImageNotFoundException(exception, image) },
+        ) {
+            httpClient
+                .prepareGet("$BasePath/$image/get")
+                .execute { response ->
+                    val totalBytes = response.contentLength()
+                    FileSystemUtils.writeChannel(Path(destinationPath), response.bodyAsChannel()) { bytesReceived ->
+                        onProgress?.invoke(bytesReceived, totalBytes)
+                    }
+                }
+        }
+
+    /**
+     * Export several images in a single tarball that can be loaded back with [load].
+     *
+     * The response is streamed straight to [destinationPath] so the images never have to be held in memory.
+     *
+     * @param images Names or ids of the images.
+     * @param destinationPath Path on the local filesystem where the tarball will be written.
+     * @param onProgress Called with the total amount of bytes received so far as the export progresses, and the size
+     *                   of the response declared by the daemon, or `null` if it's not known upfront.
+     * @return The total amount of bytes written to [destinationPath].
+     * @throws ImageNotFoundException If any of the images is not found.
+     */
+    public suspend fun save(
+        images: List<String>,
+        destinationPath: String,
+        onProgress: ((bytesReceived: Long, totalBytes: Long?) -> Unit)? = null,
+    ): Long {
+        require(images.isNotEmpty()) { "At least one image must be saved" }
+
+        return requestCatching(
+            HttpStatusCode.NotFound to { exception -> ImageNotFoundException(exception, images.joinToString()) },
+        ) {
+            httpClient
+                .prepareGet("$BasePath/get") {
+                    images.forEach { image -> parameter("names", image) }
+                }.execute { response ->
+                    val totalBytes = response.contentLength()
+                    FileSystemUtils.writeChannel(Path(destinationPath), response.bodyAsChannel()) { bytesReceived ->
+                        onProgress?.invoke(bytesReceived, totalBytes)
+                    }
+                }
+        }
+    }
 
     public suspend fun remove(
         name: String,
@@ -78,15 +287,22 @@ public class ImageResource internal constructor(
     /**
      * builds a Docker image using the specified archive path and [ImageBuildOptions].
      *
+     * The archive is streamed to the Docker daemon as it's read, so large build contexts never have to fit in memory.
+     *
      * @param archivePath The path to the build context archive (e.g., a TAR file) that contains the source code and resources.
      * @param options The [ImageBuildOptions] containing the configuration for the image build.
+     * @param onProgress Called with the total amount of bytes sent so far as the archive upload progresses, and the
+     *                   size of the archive.
      */
     public suspend fun build(
         archivePath: String,
         options: ImageBuildOptions,
+        onProgress: ((bytesSent: Long, totalBytes: Long?) -> Unit)? = null,
     ) {
+        val archive = Path(archivePath)
+        require(FileSystemUtils.exists(archive)) { "Build context archive not found: $archivePath" }
+
         httpClient.post("/build") {
-            contentType(TAR_CONTENT_TYPE)
             header("X-Registry-Config", json.encodeToString(options.registryConfig))
             parameter("dockerfile", options.path)
             parameter("t", options.tag)
@@ -113,7 +329,8 @@ public class ImageResource internal constructor(
             parameter("target", options.target)
             parameter("outputs", options.outputs)
             parameter("version", options.version)
-            setBody(archivePath)
+            onProgress?.let { listener -> onUpload { bytesSent, totalBytes -> listener(bytesSent, totalBytes) } }
+            setBody(FileContent(archive, TAR_CONTENT_TYPE))
         }
     }
 
@@ -139,3 +356,8 @@ public suspend inline fun ImageResource.build(
 ) {
     build(archivePath, ImageBuildOptions().apply(options))
 }
+
+public suspend inline fun ImageResource.import(
+    source: ImageImportSource,
+    options: ImageImportOptions.() -> Unit,
+): String = import(source, ImageImportOptions().apply(options))
@@ -0,0 +1,67 @@
+package me.devnatan.dockerkt
+
+import io.ktor.http.Cookie
+import io.ktor.http.Url
+import io.ktor.util.date.GMTDate
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.io.HttpFixtures
+import me.devnatan.dockerkt.io.Interaction
+import me.devnatan.dockerkt.io.RecordedRequest
+import me.devnatan.dockerkt.io.RecordedResponse
+import me.devnatan.dockerkt.io.withCassette
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertNotNull
+import kotlin.test.assertTrue
+
+class DockerClientCookiesTest {
+    @Test
+    fun `stores and clears cookies`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(
+                        status = 200,
+                        headers = mapOf("Set-Cookie" to listOf("session=abc123; Path=/")),
+                        body = "OK",
+                    ),
+                )
+
+            withCassette(interaction) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        cookies()
+                    }
+
+                try {
+                    client.system.ping(head = false)
+                    assertEquals(listOf("session" to "abc123"), client.cookies().map { cookie -> cookie.name to cookie.value })
+
+                    client.clearCookies()
+                    assertTrue(client.cookies().isEmpty())
+                } finally {
+                    client.close()
+                }
+            }
+        }
+
+    @Test
+    fun `turns max age into an expiry date`() =
+        runTest {
+            val jar = CookieJar()
+            val url = Url("http://localhost/v1.41/_ping")
+
+            jar.addCookie(url, Cookie("session", "abc123", maxAge = 60, expires = GMTDate(0)))
+            val expires = assertNotNull(jar.cookies().single().expires)
+            assertTrue(expires > GMTDate(), "Cookie expires at $expires")
+
+            jar.addCookie(url, Cookie("session", "", maxAge = 0))
+            assertTrue(jar.cookies().isEmpty())
+
+            jar.addCookie(url, Cookie("expired", "abc123", expires = GMTDate(0)))
+            assertTrue(jar.cookies().isEmpty())
+        }
+}
@@ -0,0 +1,69 @@
+package me.devnatan.dockerkt
+
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.request.header
+import io.ktor.client.statement.bodyAsText
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.io.HttpFixturesReplayEngine
+import me.devnatan.dockerkt.io.Interaction
+import me.devnatan.dockerkt.io.RecordedRequest
+import me.devnatan.dockerkt.io.RecordedResponse
+import me.devnatan.dockerkt.io.createRequestIdPlugin
+import me.devnatan.dockerkt.io.withCassette
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertTrue
+
+class DockerClientMetricsTest {
+    @Test
+    fun `collects request metrics`() =
+        runTest {
+            val recorder = MetricsRecorder()
+            withCassette(
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/info"),
+                    RecordedResponse(200, headers = mapOf("Content-Length" to listOf("2")), body = "{}"),
+                ),
+                Interaction(RecordedRequest("GET", "/v1.41/info"), RecordedResponse(404, body = "{}")),
+            ) { cassette ->
+                HttpClient(HttpFixturesReplayEngine(cassette)) {
+                    install(createMetricsPlugin(recorder))
+                }.use { client ->
+                    assertEquals("{}", client.get("/v1.41/info").bodyAsText())
+                    client.get("/v1.41/info")
+
+                    // no interaction left
+                    assertFailsWith<IllegalStateException> { client.get("/v1.41/info") }
+                }
+            }
+
+            val metrics = recorder.snapshot()
+            assertEquals(3, metrics.requests)
+            assertEquals(mapOf(200 to 1L, 404 to 1L), metrics.requestsByStatus)
+            assertEquals(1, metrics.failures)
+            assertEquals(0, metrics.inFlightRequests)
+            // both bodies are read, even the one without Content-Length
+            assertEquals(4, metrics.bytesReceived)
+            assertEquals(0, metrics.activeStreams)
+            assertEquals(3, metrics.latency.count)
+            assertTrue(metrics.toJson().contains("\"requestsByStatus\":{\"200\":1,\"404\":1}"), metrics.toJson())
+        }
+
+    @Test
+    fun `tags latency buckets with request ids`() =
+        runTest {
+            val recorder = MetricsRecorder()
+            withCassette(Interaction(RecordedRequest("GET", "/v1.41/info"), RecordedResponse(200, body = "{}"))) { cassette ->
+                HttpClient(HttpFixturesReplayEngine(cassette)) {
+                    install(createRequestIdPlugin("X-Request-ID"))
+                    install(createMetricsPlugin(recorder))
+                }.use { client ->
+                    client.get("/v1.41/info") { header("X-Request-ID", "abc") }
+                }
+            }
+
+            assertEquals(listOf("abc"), recorder.snapshot().latency.exemplarRequestIds.filterNotNull())
+        }
+}
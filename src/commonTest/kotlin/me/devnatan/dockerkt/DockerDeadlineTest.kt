@@ -0,0 +1,47 @@
+package me.devnatan.dockerkt
+
+import kotlinx.coroutines.currentCoroutineContext
+import kotlinx.coroutines.delay
+import kotlinx.coroutines.test.runTest
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.time.Duration.Companion.seconds
+
+class DockerDeadlineTest {
+    @Test
+    fun `fails when the deadline is exceeded`() =
+        runTest {
+            val deadline = testTimeSource.markNow() + 1.seconds
+
+            assertFailsWith<DockerDeadlineExceededException> {
+                withDeadline(deadline) {
+                    delay(2.seconds)
+                }
+            }
+        }
+
+    @Test
+    fun `completes before the deadline`() =
+        runTest {
+            val result =
+                withDeadline(testTimeSource.markNow() + 2.seconds) {
+                    delay(1.seconds)
+                    "done"
+                }
+
+            assertEquals("done", result)
+        }
+
+    @Test
+    fun `nested deadlines cannot extend the outer one`() =
+        runTest {
+            val outer = testTimeSource.markNow() + 1.seconds
+
+            withDeadline(outer) {
+                withDeadline(testTimeSource.markNow() + 10.seconds) {
+                    assertEquals(outer, currentCoroutineContext()[DockerDeadline]?.deadline)
+                }
+            }
+        }
+}
@@ -0,0 +1,41 @@
+package me.devnatan.dockerkt
+
+import me.devnatan.dockerkt.io.DefaultDockerUnixSocket
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertTrue
+
+class DockerDoctorTest {
+    @Test
+    fun `equal api versions`() {
+        assertEquals(0, compareApiVersions("1.41", "1.41"))
+    }
+
+    @Test
+    fun `api versions are compared numerically`() {
+        assertTrue(compareApiVersions("1.9", "1.41") < 0)
+        assertTrue(compareApiVersions("1.41", "1.9") > 0)
+    }
+
+    @Test
+    fun `missing api version components are treated as zero`() {
+        assertEquals(0, compareApiVersions("1", "1.0"))
+        assertTrue(compareApiVersions("1", "1.24") < 0)
+    }
+
+    @Test
+    fun `report is healthy when no check failed`() {
+        val report =
+            DoctorReport(
+                socketPath = DefaultDockerUnixSocket,
+                apiVersion = DefaultDockerApiVersion,
+                checks =
+                    listOf(
+                        DoctorCheck("Ping", DoctorCheck.Status.Passed, ""),
+                        DoctorCheck("TLS handshake", DoctorCheck.Status.Skipped, ""),
+                    ),
+            )
+        assertTrue(report.healthy)
+        assertTrue(!report.copy(checks = report.checks + DoctorCheck("API", DoctorCheck.Status.Failed, "")).healthy)
+    }
+}
@@ -0,0 +1,90 @@
+package me.devnatan.dockerkt
+
+import io.ktor.http.HttpStatusCode
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.io.HttpFixtures
+import me.devnatan.dockerkt.io.Interaction
+import me.devnatan.dockerkt.io.RecordedRequest
+import me.devnatan.dockerkt.io.RecordedResponse
+import me.devnatan.dockerkt.io.withCassette
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class DockerResponseInfoTest {
+    @Test
+    fun `reports responses to the listener`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(
+                        status = 200,
+                        headers = mapOf("Set-Cookie" to listOf("a=1", "b=2")),
+                        body = "OK",
+                    ),
+                )
+
+            val responses = mutableListOf<DockerResponseInfo>()
+            withCassette(interaction) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        responseListener(responses::add)
+                    }
+
+                try {
+                    client.system.ping(head = false)
+                } finally {
+                    client.close()
+                }
+            }
+
+            val response = responses.single()
+            assertEquals("GET", response.method)
+            assertEquals("/v1.41/_ping", response.path)
+            assertEquals(200, response.statusCode)
+            assertEquals("HTTP/1.1", response.protocolVersion)
+            assertEquals("OK", response.reasonPhrase)
+            assertEquals(listOf("a=1", "b=2"), response.headerValues("set-cookie"))
+        }
+
+    @Test
+    fun `failed calls expose the status line`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(status = 409, body = "{\"message\":\"conflict\"}"),
+                )
+
+            withCassette(interaction) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                    }
+
+                try {
+                    val exception = assertFailsWith<DockerResponseException> { client.system.ping(head = false) }
+                    assertEquals("HTTP/1.1 409 Conflict", exception.statusLine)
+                } finally {
+                    client.close()
+                }
+            }
+        }
+
+    @Test
+    fun `omits the missing reason phrase from the status line`() {
+        val exception =
+            DockerResponseException(
+                cause = null,
+                message = null,
+                statusCode = HttpStatusCode(409, ""),
+                protocolVersion = "HTTP/2.0",
+            )
+
+        assertEquals("HTTP/2.0 409", exception.statusLine)
+    }
+}
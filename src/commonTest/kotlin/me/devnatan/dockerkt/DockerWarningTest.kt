@@ -0,0 +1,111 @@
+package me.devnatan.dockerkt
+
+import io.ktor.client.request.get
+import io.ktor.client.statement.bodyAsText
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.io.HttpFixtures
+import me.devnatan.dockerkt.io.Interaction
+import me.devnatan.dockerkt.io.RecordedRequest
+import me.devnatan.dockerkt.io.RecordedResponse
+import me.devnatan.dockerkt.io.withCassette
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertTrue
+
+class DockerWarningTest {
+    @Test
+    fun `parses warning header`() {
+        assertEquals(
+            "The \"foo\" option is deprecated",
+            parseWarningHeader("299 - \"The \\\"foo\\\" option is deprecated\" \"Wed, 21 Oct 2015 07:28:00 GMT\""),
+        )
+        assertEquals("not a warning header", parseWarningHeader("not a warning header"))
+    }
+
+    @Test
+    fun `reports warning headers to the handler`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(
+                        status = 200,
+                        headers = mapOf("Warning" to listOf("299 - \"API version 1.41 is deprecated\"")),
+                        body = "OK",
+                    ),
+                )
+
+            val warnings = mutableListOf<DockerWarning>()
+            withCassette(interaction) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        warningHandler(warnings::add)
+                    }
+
+                try {
+                    client.system.ping(head = false)
+                } finally {
+                    client.close()
+                }
+            }
+
+            assertEquals(listOf(DockerWarning("API version 1.41 is deprecated", "GET", "/v1.41/_ping")), warnings)
+        }
+
+    @Test
+    fun `finds known deprecated fields`() {
+        val candidates = deprecatedFieldsFor("/v1.41/containers/abc/json", apiVersion = "1.41")
+        val found =
+            findDeprecatedFields(
+                "{\"Id\":\"abc\",\"NetworkSettings\":{\"IPAddress\":\"\",\"Networks\":{}}}",
+                candidates,
+            )
+
+        assertEquals(listOf(listOf("NetworkSettings", "IPAddress")), found.map(DeprecatedField::field))
+        assertTrue(deprecatedFieldsFor("/v1.52/containers/abc/json", apiVersion = "1.52").isEmpty())
+        assertTrue(deprecatedFieldsFor("/v1.41/containers/json", apiVersion = "1.41").isEmpty())
+    }
+
+    @Test
+    fun `reports deprecated fields once`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/images/alpine/json"),
+                    RecordedResponse(
+                        status = 200,
+                        headers = mapOf("Content-Type" to listOf("application/json")),
+                        body = "{\"Id\":\"sha256:abc\",\"VirtualSize\":1024}",
+                    ),
+                )
+
+            val warnings = mutableListOf<DockerWarning>()
+            withCassette(interaction, interaction) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        warningHandler(warnings::add)
+                    }
+
+                try {
+                    repeat(2) { client.httpClient.get("/images/alpine/json").bodyAsText() }
+                } finally {
+                    client.close()
+                }
+            }
+
+            assertEquals(
+                listOf(
+                    DockerWarning(
+                        "VirtualSize is deprecated and no longer sent from API version 1.44",
+                        "GET",
+                        "/v1.41/images/alpine/json",
+                    ),
+                ),
+                warnings,
+            )
+        }
+}
@@ -0,0 +1,17 @@
+package me.devnatan.dockerkt.io
+
+/**
+ * Writes [interactions] to a temporary cassette file and runs [block] with its path, deleting the file afterwards.
+ */
+internal inline fun <T> withCassette(
+    vararg interactions: Interaction,
+    block: (cassettePath: String) -> T,
+): T {
+    val path = FileSystemUtils.createTempFile(suffix = ".jsonl")
+    try {
+        FileSystemUtils.writeFile(path, interactions.joinToString("", transform = ::encodeInteraction).encodeToByteArray())
+        return block(path.toString())
+    } finally {
+        FileSystemUtils.delete(path)
+    }
+}
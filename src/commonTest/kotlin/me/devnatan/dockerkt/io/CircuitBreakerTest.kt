@@ -0,0 +1,42 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.http.HttpStatusCode
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerCircuitOpenException
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.time.Duration.Companion.seconds
+import kotlin.time.TestTimeSource
+
+class CircuitBreakerTest {
+    @Test
+    fun `opens after consecutive failures and closes after a successful probe`() =
+        runTest {
+            val interaction = Interaction(RecordedRequest("GET", "/v1.41/info"), RecordedResponse(200, body = "{}"))
+            val timeSource = TestTimeSource()
+            val breaker = CircuitBreaker(failureThreshold = 2, openDuration = 10.seconds, timeSource = timeSource)
+
+            withCassette(interaction) { cassette ->
+                HttpClient(HttpFixturesReplayEngine(cassette)) {
+                    install(createCircuitBreakerPlugin(breaker))
+                }.use { client ->
+                    // there's no recorded interaction for this path so the engine fails
+                    repeat(2) {
+                        assertFailsWith<IllegalStateException> { client.get("/v1.41/missing") }
+                    }
+
+                    val exception = assertFailsWith<DockerCircuitOpenException> { client.get("/v1.41/info") }
+                    assertEquals(10.seconds, exception.retryAfter)
+
+                    timeSource += 10.seconds
+                    assertEquals(HttpStatusCode.OK, client.get("/v1.41/info").status)
+
+                    // closed again, so a single failure goes through to the engine
+                    assertFailsWith<IllegalStateException> { client.get("/v1.41/missing") }
+                }
+            }
+        }
+}
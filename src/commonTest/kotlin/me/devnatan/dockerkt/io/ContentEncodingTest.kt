@@ -0,0 +1,81 @@
+package me.devnatan.dockerkt.io
+
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerResponseException
+import me.devnatan.dockerkt.DockerResponseInfo
+import me.devnatan.dockerkt.createTestDockerClient
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class ContentEncodingTest {
+    @Test
+    fun `gzip encoded responses are decoded`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(
+                        500,
+                        headers = mapOf("Content-Type" to listOf("application/json"), "Content-Encoding" to listOf("gzip")),
+                        // {"message":"daemon is broken"}
+                        base64Body = "H4sIAAAAAAACA6tWyk0tLk5MT1WyUkpJTM3Nz1PILFZIKsrPTs1TqgUAzuD/Ix4AAAA=",
+                    ),
+                )
+
+            withCassette(interaction) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        decompressResponses()
+                    }
+
+                try {
+                    val exception =
+                        assertFailsWith<DockerResponseException> {
+                            client.system.ping(head = false)
+                        }
+
+                    assertEquals("daemon is broken", exception.message)
+                    assertEquals("gzip", exception.contentEncoding)
+                } finally {
+                    client.close()
+                }
+            }
+        }
+
+    @Test
+    fun `reports the original encoding to the response listener`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(
+                        200,
+                        headers = mapOf("Content-Encoding" to listOf("gzip")),
+                        // OK
+                        base64Body = "H4sIAAAAAAAAA/P3BgAt2TbXAgAAAA==",
+                    ),
+                )
+
+            val responses = mutableListOf<DockerResponseInfo>()
+            withCassette(interaction) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        decompressResponses()
+                        responseListener(responses::add)
+                    }
+
+                try {
+                    client.system.ping(head = false)
+                } finally {
+                    client.close()
+                }
+            }
+
+            assertEquals("gzip", responses.single().contentEncoding)
+        }
+}
@@ -0,0 +1,75 @@
+package me.devnatan.dockerkt.io
+
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class DockerEndpointTest {
+    @Test
+    fun `parses unix socket endpoint`() {
+        assertEquals(DockerEndpoint.Unix("/var/run/docker.sock"), DockerEndpoint.parse(DefaultDockerUnixSocket))
+    }
+
+    @Test
+    fun `parses named pipe endpoint`() {
+        assertEquals(DockerEndpoint.NamedPipe("\\\\.\\pipe\\docker_engine"), DockerEndpoint.parse(DefaultDockerNamedPipe))
+    }
+
+    @Test
+    fun `parses tcp endpoint`() {
+        assertEquals(DockerEndpoint.Tcp("localhost", 2375, tls = false), DockerEndpoint.parse("tcp://localhost:2375"))
+    }
+
+    @Test
+    fun `defaults tcp endpoint port`() {
+        assertEquals(DockerEndpoint.Tcp("docker.local", 2375, tls = false), DockerEndpoint.parse("tcp://docker.local"))
+        assertEquals(DockerEndpoint.Tcp("docker.local", 2376, tls = true), DockerEndpoint.parse("https://docker.local"))
+    }
+
+    @Test
+    fun `hints tls on tls port`() {
+        assertEquals(DockerEndpoint.Tcp("docker.local", 2376, tls = true), DockerEndpoint.parse("tcp://docker.local:2376"))
+    }
+
+    @Test
+    fun `parses ipv6 tcp endpoint`() {
+        assertEquals(DockerEndpoint.Tcp("::1", 2375, tls = false), DockerEndpoint.parse("tcp://[::1]:2375"))
+    }
+
+    @Test
+    fun `parses ssh endpoint`() {
+        assertEquals(DockerEndpoint.Ssh("deploy", "docker.local", 22), DockerEndpoint.parse("ssh://deploy@docker.local"))
+        assertEquals(DockerEndpoint.Ssh(null, "docker.local", 2222), DockerEndpoint.parse("ssh://docker.local:2222"))
+    }
+
+    @Test
+    fun `parses fd endpoint`() {
+        assertEquals(DockerEndpoint.Fd(null), DockerEndpoint.parse("fd://"))
+        assertEquals(DockerEndpoint.Fd("docker.socket"), DockerEndpoint.parse("fd://docker.socket"))
+    }
+
+    @Test
+    fun `matches schemes regardless of their case`() {
+        assertEquals(DockerEndpoint.Unix("/var/run/docker.sock"), DockerEndpoint.parse("UNIX:///var/run/docker.sock"))
+        assertEquals(
+            DockerEndpoint.NamedPipe("\\\\.\\pipe\\docker_engine"),
+            DockerEndpoint.parse("NPipe:////./pipe/docker_engine"),
+        )
+        assertEquals(DockerEndpoint.Tcp("docker.local", 2376, tls = true), DockerEndpoint.parse("HTTPS://docker.local"))
+    }
+
+    @Test
+    fun `rejects invalid endpoints`() {
+        listOf(
+            "/var/run/docker.sock",
+            "unix://",
+            "tcp://",
+            "tcp://localhost:port",
+            "tcp://localhost:65536",
+            "tcp://[::1",
+            "ftp://localhost",
+        ).forEach { endpoint ->
+            assertFailsWith<IllegalArgumentException>(endpoint) { DockerEndpoint.parse(endpoint) }
+        }
+    }
+}
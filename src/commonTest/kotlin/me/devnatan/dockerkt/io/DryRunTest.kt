@@ -0,0 +1,71 @@
+package me.devnatan.dockerkt.io
+
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerDryRunException
+import me.devnatan.dockerkt.createTestDockerClient
+import me.devnatan.dockerkt.models.container.ContainerListOptions
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertTrue
+
+class DryRunTest {
+    @Test
+    fun `requests are not sent in dry run mode`() =
+        runTest {
+            val client = createTestDockerClient { dryRun() }
+            try {
+                val exception =
+                    assertFailsWith<DockerDryRunException> {
+                        client.containers.list(ContainerListOptions(all = true, limit = 5))
+                    }
+
+                val request = exception.request
+                assertEquals("GET", request.method)
+                assertTrue(request.url.contains("/containers/json?"), "Unexpected URL: ${request.url}")
+                assertTrue(request.url.contains("all=true"), "Unexpected URL: ${request.url}")
+                assertTrue(request.url.contains("limit=5"), "Unexpected URL: ${request.url}")
+            } finally {
+                client.close()
+            }
+        }
+
+    @Test
+    fun `default headers are sent on every request`() =
+        runTest {
+            val client =
+                createTestDockerClient {
+                    dryRun()
+                    userAgent("my-app/1.0")
+                    defaultHeader("X-Registry-Auth", "e30=")
+                    defaultHeader("X-Custom", "a")
+                    defaultHeader("X-Custom", "b")
+                }
+            try {
+                val exception = assertFailsWith<DockerDryRunException> { client.system.ping() }
+
+                val headers = exception.request.headers
+                assertEquals(listOf("my-app/1.0"), headers["User-Agent"])
+                assertEquals(listOf("e30="), headers["X-Registry-Auth"])
+                assertEquals(listOf("a", "b"), headers["X-Custom"])
+            } finally {
+                client.close()
+            }
+        }
+
+    @Test
+    fun `wire format of a request`() {
+        val request =
+            DryRunRequest(
+                method = "POST",
+                url = "http://localhost:2375/v1.41/containers/create?name=test",
+                headers = mapOf("Content-Type" to listOf("application/json")),
+                body = "{}",
+            )
+
+        assertEquals(
+            expected = "POST /v1.41/containers/create?name=test HTTP/1.1\r\nContent-Type: application/json\r\n\r\n{}",
+            actual = request.toWireFormat(),
+        )
+    }
+}
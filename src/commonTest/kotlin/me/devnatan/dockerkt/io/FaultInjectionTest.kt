@@ -0,0 +1,76 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.statement.bodyAsText
+import io.ktor.http.HttpStatusCode
+import kotlinx.coroutines.test.runTest
+import kotlinx.io.IOException
+import me.devnatan.dockerkt.DockerResponseException
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class FaultInjectionTest {
+    private inline fun <T> withClient(
+        faults: FaultInjection,
+        block: (HttpClient) -> T,
+    ): T {
+        val interaction =
+            Interaction(
+                RecordedRequest("GET", "/v1.41/info"),
+                RecordedResponse(200, body = "0123456789"),
+            )
+
+        return withCassette(interaction) { cassette ->
+            HttpClient(HttpFixturesReplayEngine(cassette)) {
+                install(createFaultInjectionPlugin(faults))
+            }.use(block)
+        }
+    }
+
+    @Test
+    fun `no faults are injected by default`() =
+        runTest {
+            withClient(FaultInjection()) { client ->
+                assertEquals("0123456789", client.get("/v1.41/info").bodyAsText())
+            }
+        }
+
+    @Test
+    fun `drops connections`() =
+        runTest {
+            withClient(FaultInjection(connectionDropRate = 1.0)) { client ->
+                assertFailsWith<IOException> {
+                    client.get("/v1.41/info")
+                }
+            }
+        }
+
+    @Test
+    fun `injects error status`() =
+        runTest {
+            withClient(FaultInjection(errorRate = 1.0, errorStatus = HttpStatusCode.BadGateway)) { client ->
+                val exception =
+                    assertFailsWith<DockerResponseException> {
+                        client.get("/v1.41/info")
+                    }
+                assertEquals(HttpStatusCode.BadGateway, exception.statusCode)
+            }
+        }
+
+    @Test
+    fun `truncates response body`() =
+        runTest {
+            withClient(FaultInjection(truncateRate = 1.0, truncateAfterBytes = 4)) { client ->
+                assertEquals("0123", client.get("/v1.41/info").bodyAsText())
+            }
+        }
+
+    @Test
+    fun `rejects invalid rates`() {
+        assertFailsWith<IllegalArgumentException> {
+            FaultInjection(errorRate = 1.5)
+        }
+    }
+}
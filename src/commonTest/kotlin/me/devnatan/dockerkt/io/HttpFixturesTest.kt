@@ -0,0 +1,106 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.request.post
+import io.ktor.client.request.setBody
+import io.ktor.client.statement.bodyAsText
+import io.ktor.client.statement.readRawBytes
+import io.ktor.http.HttpStatusCode
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.util.DockerKotlinJson
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class HttpFixturesTest {
+    @Test
+    fun `replays recorded interactions in order`() =
+        runTest {
+            withCassette(
+                Interaction(RecordedRequest("GET", "/v1.41/containers/json"), RecordedResponse(200, body = "[]")),
+                Interaction(RecordedRequest("GET", "/v1.41/containers/json"), RecordedResponse(500, body = "{}")),
+            ) { cassette ->
+                HttpClient(HttpFixturesReplayEngine(cassette)).use { client ->
+                    val first = client.get("/v1.41/containers/json")
+                    assertEquals(HttpStatusCode.OK, first.status)
+                    assertEquals("[]", first.bodyAsText())
+
+                    val second = client.get("/v1.41/containers/json")
+                    assertEquals(HttpStatusCode.InternalServerError, second.status)
+                }
+            }
+        }
+
+    @Test
+    fun `replays interactions matching the request body`() =
+        runTest {
+            withCassette(
+                Interaction(RecordedRequest("POST", "/v1.41/build", "first"), RecordedResponse(200, body = "1")),
+                Interaction(RecordedRequest("POST", "/v1.41/build", "second"), RecordedResponse(200, body = "2")),
+            ) { cassette ->
+                HttpClient(HttpFixturesReplayEngine(cassette)).use { client ->
+                    assertEquals("2", client.post("/v1.41/build") { setBody("second") }.bodyAsText())
+                    assertEquals("1", client.post("/v1.41/build") { setBody("first") }.bodyAsText())
+                }
+            }
+        }
+
+    @Test
+    fun `appends recorded interactions to the cassette`() =
+        runTest {
+            val interaction = Interaction(RecordedRequest("GET", "/v1.41/info"), RecordedResponse(200, body = "{}"))
+            val recorded = FileSystemUtils.createTempFile(suffix = ".jsonl")
+            try {
+                FileSystemUtils.writeFile(recorded, encodeInteraction(interaction).encodeToByteArray())
+
+                withCassette(interaction) { cassette ->
+                    HttpClient(HttpFixturesReplayEngine(cassette)) {
+                        install(createHttpFixturesRecorder(recorded.toString()))
+                    }.use { client ->
+                        client.get("/v1.41/info").bodyAsText()
+                    }
+                }
+
+                val lines = FileSystemUtils.readFile(recorded).decodeToString().lines().filter(String::isNotBlank)
+                assertEquals(2, lines.size)
+                assertEquals(interaction.request, DockerKotlinJson.decodeFromString<Interaction>(lines.last()).request)
+            } finally {
+                FileSystemUtils.delete(recorded)
+            }
+        }
+
+    @Test
+    fun `replays binary bodies`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/containers/abc/export"),
+                    RecordedResponse(
+                        status = 200,
+                        headers = mapOf("Content-Type" to listOf("application/x-tar")),
+                        base64Body = "AAEC",
+                    ),
+                )
+
+            withCassette(interaction) { cassette ->
+                HttpClient(HttpFixturesReplayEngine(cassette)).use { client ->
+                    val response = client.get("/v1.41/containers/abc/export")
+                    assertEquals("application/x-tar", response.headers["Content-Type"])
+                    assertEquals(listOf<Byte>(0, 1, 2), response.readRawBytes().toList())
+                }
+            }
+        }
+
+    @Test
+    fun `fails when no interaction matches`() =
+        runTest {
+            withCassette { cassette ->
+                HttpClient(HttpFixturesReplayEngine(cassette)).use { client ->
+                    assertFailsWith<IllegalStateException> {
+                        client.get("/v1.41/info")
+                    }
+                }
+            }
+        }
+}
@@ -0,0 +1,65 @@
+package me.devnatan.dockerkt.io
+
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerResponseException
+import me.devnatan.dockerkt.DockerResponseInfo
+import me.devnatan.dockerkt.createTestDockerClient
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertTrue
+
+class RedirectPolicyTest {
+    private val interactions =
+        arrayOf(
+            Interaction(
+                RecordedRequest("GET", "/v1.41/_ping"),
+                RecordedResponse(302, headers = mapOf("Location" to listOf("/v1.41/_ping?redirected=true"))),
+            ),
+            Interaction(RecordedRequest("GET", "/v1.41/_ping?redirected=true"), RecordedResponse(200, body = "OK")),
+        )
+
+    @Test
+    fun `follows redirects and reports the visited urls`() =
+        runTest {
+            val responses = mutableListOf<DockerResponseInfo>()
+            withCassette(*interactions) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        responseListener(responses::add)
+                    }
+
+                try {
+                    client.system.ping(head = false)
+                } finally {
+                    client.close()
+                }
+            }
+
+            val redirects = responses.last().redirects
+            assertEquals(1, redirects.size)
+            assertTrue(redirects.single().endsWith("/v1.41/_ping"), redirects.single())
+        }
+
+    @Test
+    fun `does not follow redirects when disabled`() =
+        runTest {
+            withCassette(*interactions) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        redirectPolicy(RedirectPolicy.None)
+                    }
+
+                try {
+                    val exception = assertFailsWith<DockerResponseException> { client.system.ping(head = false) }
+                    assertEquals(302, exception.statusCode.value)
+                } finally {
+                    client.close()
+                }
+            }
+        }
+}
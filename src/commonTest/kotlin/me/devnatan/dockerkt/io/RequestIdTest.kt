@@ -0,0 +1,77 @@
+package me.devnatan.dockerkt.io
+
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerResponseException
+import me.devnatan.dockerkt.DockerResponseInfo
+import me.devnatan.dockerkt.DockerWarning
+import me.devnatan.dockerkt.createTestDockerClient
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertNotNull
+
+class RequestIdTest {
+    @Test
+    fun `failed calls expose the id they were sent with`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(500, body = "{\"message\":\"daemon is broken\"}"),
+                )
+
+            withCassette(interaction) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        requestIds("X-Correlation-ID")
+                    }
+
+                try {
+                    val exception =
+                        assertFailsWith<DockerResponseException> {
+                            client.system.ping(head = false)
+                        }
+
+                    assertEquals("daemon is broken", exception.message)
+                    assertNotNull(exception.requestId)
+                    assertEquals(36, exception.requestId?.length)
+                } finally {
+                    client.close()
+                }
+            }
+        }
+
+    @Test
+    fun `reports the id to the response listener and warning handler`() =
+        runTest {
+            val interaction =
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(200, headers = mapOf("Warning" to listOf("299 - \"deprecated\"")), body = "OK"),
+                )
+
+            val responses = mutableListOf<DockerResponseInfo>()
+            val warnings = mutableListOf<DockerWarning>()
+            withCassette(interaction) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        requestIds()
+                        responseListener(responses::add)
+                        warningHandler(warnings::add)
+                    }
+
+                try {
+                    client.system.ping(head = false)
+                } finally {
+                    client.close()
+                }
+            }
+
+            val requestId = assertNotNull(responses.single().requestId)
+            assertEquals(requestId, warnings.single().requestId)
+        }
+}
@@ -0,0 +1,85 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.plugins.cache.storage.CachedResponseData
+import io.ktor.http.Headers
+import io.ktor.http.HttpProtocolVersion
+import io.ktor.http.HttpStatusCode
+import io.ktor.http.Url
+import io.ktor.util.date.GMTDate
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerResponseInfo
+import me.devnatan.dockerkt.createTestDockerClient
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertNotNull
+import kotlin.test.assertNull
+
+class ResponseCacheTest {
+    @Test
+    fun `not modified responses are served from the cache`() =
+        runTest {
+            val responses = mutableListOf<DockerResponseInfo>()
+            withCassette(
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(
+                        status = 200,
+                        headers =
+                            mapOf(
+                                "ETag" to listOf("\"v1\""),
+                                "Cache-Control" to listOf("no-cache"),
+                                "API-Version" to listOf("1.41"),
+                            ),
+                        body = "OK",
+                    ),
+                ),
+                Interaction(RecordedRequest("GET", "/v1.41/_ping"), RecordedResponse(304)),
+            ) { cassette ->
+                val client =
+                    createTestDockerClient {
+                        apiVersion("1.41")
+                        httpFixtures(HttpFixtures.Replay(cassette))
+                        responseCache()
+                        responseListener(responses::add)
+                    }
+
+                try {
+                    assertEquals("1.41", client.system.ping(head = false).apiVersion)
+                    assertEquals("1.41", client.system.ping(head = false).apiVersion)
+                } finally {
+                    client.close()
+                }
+            }
+
+            assertEquals(listOf(false, true), responses.map(DockerResponseInfo::fromCache))
+        }
+
+    private fun cachedResponse(url: Url) =
+        CachedResponseData(
+            url = url,
+            statusCode = HttpStatusCode.OK,
+            requestTime = GMTDate(),
+            responseTime = GMTDate(),
+            version = HttpProtocolVersion.HTTP_1_1,
+            expires = GMTDate(),
+            headers = Headers.Empty,
+            varyKeys = emptyMap(),
+            body = ByteArray(0),
+        )
+
+    @Test
+    fun `evicts the least recently used urls`() =
+        runTest {
+            val storage = LruCacheStorage(maxUrls = 2)
+            val (first, second, third) = listOf("a", "b", "c").map { name -> Url("http://localhost/v1.41/$name") }
+
+            storage.store(first, cachedResponse(first))
+            storage.store(second, cachedResponse(second))
+            assertNotNull(storage.find(first, emptyMap()))
+            storage.store(third, cachedResponse(third))
+
+            assertNotNull(storage.find(first, emptyMap()))
+            assertNull(storage.find(second, emptyMap()))
+            assertNotNull(storage.find(third, emptyMap()))
+        }
+}
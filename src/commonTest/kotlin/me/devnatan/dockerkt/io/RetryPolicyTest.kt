@@ -0,0 +1,127 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.http.toHttpDate
+import io.ktor.util.date.GMTDate
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerClient
+import me.devnatan.dockerkt.DockerResponseException
+import me.devnatan.dockerkt.createTestDockerClient
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertNull
+import kotlin.time.Duration.Companion.milliseconds
+import kotlin.time.Duration.Companion.minutes
+import kotlin.time.Duration.Companion.seconds
+
+class RetryPolicyTest {
+    private suspend fun withRetryingClient(
+        policy: RetryPolicy,
+        vararg interactions: Interaction,
+        block: suspend (DockerClient) -> Unit,
+    ) = withCassette(*interactions) { cassette ->
+        val client =
+            createTestDockerClient {
+                apiVersion("1.41")
+                httpFixtures(HttpFixtures.Replay(cassette))
+                retryPolicy(policy)
+            }
+
+        try {
+            block(client)
+        } finally {
+            client.close()
+        }
+    }
+
+    @Test
+    fun `backoff doubles up to the max delay`() {
+        val policy = RetryPolicy(baseDelay = 100.milliseconds, maxDelay = 1.seconds, jitter = 0.0)
+
+        assertEquals(100.milliseconds, policy.delayBefore(1))
+        assertEquals(200.milliseconds, policy.delayBefore(2))
+        assertEquals(400.milliseconds, policy.delayBefore(3))
+        assertEquals(1.seconds, policy.delayBefore(5))
+    }
+
+    @Test
+    fun `parses Retry-After as seconds or an HTTP date`() {
+        val now = GMTDate(timestamp = 1_700_000_000_000)
+
+        assertEquals(5.seconds, parseRetryAfter("5", now))
+        assertEquals(30.seconds, parseRetryAfter(GMTDate(timestamp = 1_700_000_030_000).toHttpDate(), now))
+        assertEquals(0.seconds, parseRetryAfter(GMTDate(timestamp = 1_699_999_990_000).toHttpDate(), now))
+        assertNull(parseRetryAfter("soon", now))
+    }
+
+    @Test
+    fun `retries after the delay requested by Retry-After`() =
+        runTest {
+            withRetryingClient(
+                RetryPolicy(),
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(429, mapOf("Retry-After" to listOf("1")), "{\"message\":\"slow down\"}"),
+                ),
+                Interaction(RecordedRequest("GET", "/v1.41/_ping"), RecordedResponse(200, body = "OK")),
+            ) { client ->
+                client.system.ping(head = false)
+            }
+        }
+
+    @Test
+    fun `does not wait longer than the max Retry-After`() =
+        runTest {
+            withRetryingClient(
+                RetryPolicy(maxRetryAfter = 1.minutes),
+                Interaction(
+                    RecordedRequest("GET", "/v1.41/_ping"),
+                    RecordedResponse(503, mapOf("Retry-After" to listOf("3600")), "{\"message\":\"maintenance\"}"),
+                ),
+                Interaction(RecordedRequest("GET", "/v1.41/_ping"), RecordedResponse(200, body = "OK")),
+            ) { client ->
+                val exception = assertFailsWith<DockerResponseException> { client.system.ping(head = false) }
+                assertEquals(503, exception.statusCode.value)
+            }
+        }
+
+    @Test
+    fun `retries idempotent calls on retryable statuses`() =
+        runTest {
+            withRetryingClient(
+                RetryPolicy(),
+                Interaction(RecordedRequest("GET", "/v1.41/_ping"), RecordedResponse(503, body = "{\"message\":\"restarting\"}")),
+                Interaction(RecordedRequest("GET", "/v1.41/_ping"), RecordedResponse(200, body = "OK")),
+            ) { client ->
+                client.system.ping(head = false)
+            }
+        }
+
+    @Test
+    fun `gives up after max attempts`() =
+        runTest {
+            val unavailable =
+                Interaction(RecordedRequest("GET", "/v1.41/_ping"), RecordedResponse(503, body = "{\"message\":\"restarting\"}"))
+
+            withRetryingClient(RetryPolicy(maxAttempts = 2), unavailable, unavailable) { client ->
+                val exception = assertFailsWith<DockerResponseException> { client.system.ping(head = false) }
+                assertEquals(503, exception.statusCode.value)
+            }
+        }
+
+    @Test
+    fun `does not retry non-idempotent calls`() =
+        runTest {
+            withRetryingClient(
+                RetryPolicy(),
+                Interaction(
+                    RecordedRequest("POST", "/v1.41/containers/foo/start"),
+                    RecordedResponse(503, body = "{\"message\":\"restarting\"}"),
+                ),
+                Interaction(RecordedRequest("POST", "/v1.41/containers/foo/start"), RecordedResponse(204)),
+            ) { client ->
+                val exception = assertFailsWith<DockerResponseException> { client.containers.start("foo") }
+                assertEquals(503, exception.statusCode.value)
+            }
+        }
+}
@@ -64,12 +64,8 @@ class SocketsTest {
     }
 
     @Test
-    fun `isUnixSocket returns true for unix prefix`() {
-        assertTrue(isUnixSocket("unix:///var/run/docker.sock"))
-    }
-
-    @Test
-    fun `isUnixSocket returns false for tcp prefix`() {
-        assertTrue(!isUnixSocket("tcp://localhost:2375"))
+    fun `named pipe path roundtrips through hostname encoding`() {
+        val path = (DockerEndpoint.parse(DefaultDockerNamedPipe) as DockerEndpoint.NamedPipe).path
+        assertEquals(path, decodeSocketPathHostname(encodeSocketPathHostname(path)))
     }
 }
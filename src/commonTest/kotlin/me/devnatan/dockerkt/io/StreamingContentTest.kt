@@ -0,0 +1,44 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.http.ContentType
+import io.ktor.utils.io.ByteChannel
+import io.ktor.utils.io.readRemaining
+import kotlinx.coroutines.launch
+import kotlinx.coroutines.test.runTest
+import kotlinx.io.readByteArray
+import kotlin.test.Test
+import kotlin.test.assertContentEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertNull
+
+class StreamingContentTest {
+    @Test
+    fun `writes every filled chunk`() =
+        runTest {
+            val chunks = ArrayDeque(listOf("docker".encodeToByteArray(), "-".encodeToByteArray(), "kotlin".encodeToByteArray()))
+            val content =
+                StreamingContent(ContentType.Application.OctetStream) { buffer ->
+                    val chunk = chunks.removeFirstOrNull() ?: return@StreamingContent -1
+                    chunk.copyInto(buffer)
+                    chunk.size
+                }
+            assertNull(content.contentLength)
+
+            val channel = ByteChannel()
+            launch {
+                content.writeTo(channel)
+                channel.flushAndClose()
+            }
+
+            assertContentEquals("docker-kotlin".encodeToByteArray(), channel.readRemaining().readByteArray())
+        }
+
+    @Test
+    fun `rejects fill larger than the buffer`() =
+        runTest {
+            val content = StreamingContent(ContentType.Application.OctetStream) { buffer -> buffer.size + 1 }
+            assertFailsWith<IllegalStateException> {
+                content.writeTo(ByteChannel())
+            }
+        }
+}
@@ -0,0 +1,66 @@
+package me.devnatan.dockerkt.io
+
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerDryRunException
+import me.devnatan.dockerkt.createTestDockerClient
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertNotNull
+import kotlin.test.assertNull
+import kotlin.test.assertTrue
+
+class TracePropagationTest {
+    private suspend fun sentHeaders(parentContext: () -> TraceContext?): Map<String, List<String>> {
+        val client =
+            createTestDockerClient {
+                dryRun()
+                tracePropagation(parentContext)
+            }
+
+        try {
+            return assertFailsWith<DockerDryRunException> { client.system.ping() }.request.headers
+        } finally {
+            client.close()
+        }
+    }
+
+    @Test
+    fun `starts a new trace without parent context`() =
+        runTest {
+            val traceParent = sentHeaders { null }["traceparent"]?.single()
+            assertNotNull(traceParent)
+
+            val context = TraceContext.parse(traceParent)
+            assertNotNull(context, "Invalid traceparent: $traceParent")
+            assertTrue(context.sampled)
+        }
+
+    @Test
+    fun `propagates parent context`() =
+        runTest {
+            val parent =
+                TraceContext(
+                    traceId = "4bf92f3577b34da6a3ce929d0e0e4736",
+                    spanId = "00f067aa0ba902b7",
+                    sampled = false,
+                    traceState = "vendor=value",
+                )
+
+            val headers = sentHeaders { parent }
+            val context = TraceContext.parse(headers.getValue("traceparent").single())
+            assertNotNull(context)
+            assertEquals(parent.copy(traceState = null), context)
+            assertEquals(listOf("vendor=value"), headers["tracestate"])
+        }
+
+    @Test
+    fun `parses traceparent header`() {
+        val context = TraceContext.parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        assertEquals(TraceContext("4bf92f3577b34da6a3ce929d0e0e4736", "00f067aa0ba902b7", sampled = true), context)
+
+        assertNull(TraceContext.parse("00-00000000000000000000000000000000-00f067aa0ba902b7-01"))
+        assertNull(TraceContext.parse("not a traceparent"))
+        assertNull(TraceContext.parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra"))
+    }
+}
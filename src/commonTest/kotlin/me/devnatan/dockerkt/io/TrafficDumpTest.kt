@@ -0,0 +1,102 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.request.header
+import io.ktor.client.request.post
+import io.ktor.client.request.setBody
+import io.ktor.client.statement.bodyAsText
+import kotlinx.coroutines.test.runTest
+import kotlinx.io.files.Path
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFalse
+import kotlin.test.assertTrue
+
+class TrafficDumpTest {
+    private inline fun <T> withClient(
+        dump: TrafficDump,
+        vararg interactions: Interaction,
+        block: (HttpClient) -> T,
+    ): T =
+        withCassette(*interactions) { cassette ->
+            HttpClient(HttpFixturesReplayEngine(cassette)) {
+                install(createTrafficDumpPlugin(dump))
+            }.use(block)
+        }
+
+    @Test
+    fun `dumps calls with credentials redacted`() =
+        runTest {
+            val directory = FileSystemUtils.createTempDirectory()
+            try {
+                val dump = TrafficDump(Path(directory, "dump.log").toString())
+                val interaction =
+                    Interaction(
+                        RecordedRequest("POST", "/v1.41/auth", "{\"username\":\"devnatan\",\"password\":\"hunter2\"}"),
+                        RecordedResponse(200, body = "{\"Status\":\"Login Succeeded\"}"),
+                    )
+
+                withClient(dump, interaction) { client ->
+                    val response =
+                        client.post("/v1.41/auth") {
+                            header("X-Registry-Auth", "secret-token")
+                            setBody("{\"username\":\"devnatan\",\"password\":\"hunter2\"}")
+                        }
+                    assertEquals("{\"Status\":\"Login Succeeded\"}", response.bodyAsText())
+                }
+
+                val contents = FileSystemUtils.readFile(Path(dump.path)).decodeToString()
+                assertTrue(contents.contains("POST"), contents)
+                assertTrue(contents.contains("X-Registry-Auth: <redacted>"), contents)
+                assertTrue(contents.contains("\"password\":\"<redacted>\""), contents)
+                assertTrue(contents.contains("Login Succeeded"), contents)
+                assertFalse(contents.contains("secret-token"), contents)
+                assertFalse(contents.contains("hunter2"), contents)
+            } finally {
+                FileSystemUtils.deleteRecursively(directory)
+            }
+        }
+
+    @Test
+    fun `rotates the dump file`() =
+        runTest {
+            val directory = FileSystemUtils.createTempDirectory()
+            try {
+                val dump = TrafficDump(Path(directory, "dump.log").toString(), maxFileSize = 64, maxFiles = 2)
+                val interaction = Interaction(RecordedRequest("GET", "/v1.41/info"), RecordedResponse(200, body = "{}"))
+
+                withClient(dump, interaction, interaction, interaction) { client ->
+                    repeat(3) { client.get("/v1.41/info").bodyAsText() }
+                }
+
+                assertTrue(FileSystemUtils.exists(Path(dump.path)))
+                assertTrue(FileSystemUtils.exists(Path("${dump.path}.1")))
+                assertFalse(FileSystemUtils.exists(Path("${dump.path}.2")))
+            } finally {
+                FileSystemUtils.deleteRecursively(directory)
+            }
+        }
+
+    @Test
+    fun `dumps binary bodies base64 encoded`() =
+        runTest {
+            val directory = FileSystemUtils.createTempDirectory()
+            try {
+                val dump = TrafficDump(Path(directory, "dump.log").toString())
+                // a multiplexed log stream frame, whose header isn't text
+                val interaction =
+                    Interaction(
+                        RecordedRequest("GET", "/v1.41/containers/abc/logs"),
+                        RecordedResponse(200, base64Body = "AQAAAAAAAAJoaQ=="),
+                    )
+
+                withClient(dump, interaction) { client -> client.get("/v1.41/containers/abc/logs").bodyAsText() }
+
+                val contents = FileSystemUtils.readFile(Path(dump.path)).decodeToString()
+                assertTrue(contents.contains("<binary body, base64 encoded> AQAAAAAAAAJoaQ=="), contents)
+            } finally {
+                FileSystemUtils.deleteRecursively(directory)
+            }
+        }
+}
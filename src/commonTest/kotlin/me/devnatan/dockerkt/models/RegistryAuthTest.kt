@@ -0,0 +1,38 @@
+package me.devnatan.dockerkt.models
+
+import kotlin.io.encoding.Base64
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertTrue
+
+class RegistryAuthTest {
+    @Test
+    fun `encodes password credentials and wipes them`() {
+        val password = "hunter2".toCharArray()
+        val header = RegistryAuth.Password("devnatan", password, "registry.example.com").encodeAndWipe()
+
+        assertEquals(
+            "{\"username\":\"devnatan\",\"password\":\"hunter2\",\"serveraddress\":\"registry.example.com\"}",
+            Base64.UrlSafe.decode(header).decodeToString(),
+        )
+        assertTrue(password.all { it == Char.MIN_VALUE })
+    }
+
+    @Test
+    fun `encodes identity tokens and wipes them`() {
+        val token = "identity-token".toCharArray()
+        val header = RegistryAuth.IdentityToken(token).encodeAndWipe()
+
+        assertEquals("{\"identitytoken\":\"identity-token\"}", Base64.UrlSafe.decode(header).decodeToString())
+        assertTrue(token.all { it == Char.MIN_VALUE })
+    }
+
+    @Test
+    fun `wiped credentials cannot be encoded again`() {
+        val auth = RegistryAuth.IdentityToken("identity-token".toCharArray())
+        auth.encodeAndWipe()
+
+        assertFailsWith<IllegalStateException> { auth.encodeAndWipe() }
+    }
+}
@@ -0,0 +1,37 @@
+package me.devnatan.dockerkt.resource.container
+
+import kotlinx.coroutines.flow.first
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.resource.ResourceIT
+import me.devnatan.dockerkt.withContainer
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class AttachContainerIT : ResourceIT() {
+    @Test
+    fun `attach to container over websocket`() =
+        runTest {
+            testClient.withContainer(
+                "busybox:latest",
+                {
+                    command = listOf("cat")
+                    openStdin = true
+                },
+            ) { id ->
+                testClient.containers.start(id)
+                testClient.containers.attachWebSocket(id) {
+                    send("hello\n")
+                    assertEquals("hello\n", output.first().decodeToString())
+                }
+            }
+        }
+
+    @Test
+    fun `throws ContainerNotFoundException on attach to unknown container over websocket`() =
+        runTest {
+            assertFailsWith<ContainerNotFoundException> {
+                testClient.containers.attachWebSocket("santo-bastao") {}
+            }
+        }
+}
@@ -0,0 +1,48 @@
+package me.devnatan.dockerkt.resource.container
+
+import kotlinx.coroutines.test.runTest
+import kotlinx.io.files.Path
+import me.devnatan.dockerkt.io.FileSystemUtils
+import me.devnatan.dockerkt.resource.ResourceIT
+import me.devnatan.dockerkt.withContainer
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertTrue
+
+class ExportContainerIT : ResourceIT() {
+    @Test
+    fun `export container to file`() =
+        runTest {
+            testClient.withContainer("busybox:latest") { id ->
+                val tempDir = FileSystemUtils.createTempDirectory()
+                try {
+                    val destination = Path(tempDir, "export.tar")
+                    var lastProgress = 0L
+                    val written =
+                        testClient.containers.export(id, destination.toString()) { bytesReceived, _ ->
+                            lastProgress = bytesReceived
+                        }
+
+                    assertTrue(written > 0, "Exported tarball must not be empty")
+                    assertEquals(written, lastProgress)
+                    assertEquals(written, FileSystemUtils.getMetadata(destination)?.size)
+                } finally {
+                    FileSystemUtils.deleteRecursively(tempDir)
+                }
+            }
+        }
+
+    @Test
+    fun `throws ContainerNotFoundException on export a unknown container`() =
+        runTest {
+            val tempDir = FileSystemUtils.createTempDirectory()
+            try {
+                assertFailsWith<ContainerNotFoundException> {
+                    testClient.containers.export("santo-bastao", Path(tempDir, "export.tar").toString())
+                }
+            } finally {
+                FileSystemUtils.deleteRecursively(tempDir)
+            }
+        }
+}
@@ -2,9 +2,15 @@ package me.devnatan.dockerkt.resource.image
 
 import kotlinx.coroutines.flow.collect
 import kotlinx.coroutines.test.runTest
+import kotlinx.io.files.Path
+import me.devnatan.dockerkt.io.FileSystemUtils
+import me.devnatan.dockerkt.models.image.ImageImportSource
+import me.devnatan.dockerkt.models.image.ImageLoadSource
 import me.devnatan.dockerkt.resource.ResourceIT
+import me.devnatan.dockerkt.withContainer
 import me.devnatan.dockerkt.withImage
 import kotlin.test.Test
+import kotlin.test.assertEquals
 import kotlin.test.assertFailsWith
 import kotlin.test.assertNotNull
 import kotlin.test.assertTrue
@@ -71,4 +77,89 @@ class ImageIT : ResourceIT() {
                 assertNotNull(info)
             }
         }
+
+    @Test
+    fun `image import from file`() =
+        runTest {
+            testClient.withContainer("busybox:latest") { containerId ->
+                val tempDir = FileSystemUtils.createTempDirectory()
+                try {
+                    val tarball = Path(tempDir, "rootfs.tar")
+                    testClient.containers.export(containerId, tarball.toString())
+
+                    val imageId =
+                        testClient.images.import(ImageImportSource.File(tarball.toString())) {
+                            repository = "docker-kotlin-import"
+                            tag = "test"
+                        }
+
+                    try {
+                        assertEquals(imageId, testClient.images.inspect("docker-kotlin-import:test").id)
+                    } finally {
+                        testClient.images.remove(imageId, force = true)
+                    }
+                } finally {
+                    FileSystemUtils.deleteRecursively(tempDir)
+                }
+            }
+        }
+
+    @Test
+    fun `image build fails on missing context archive`() =
+        runTest {
+            assertFailsWith<IllegalArgumentException> {
+                testClient.images.build("/non/existent/context.tar") {
+                    tag = "docker-kotlin-build:test"
+                }
+            }
+        }
+
+    @Test
+    fun `image save and load with progress`() =
+        runTest {
+            testClient.withImage("busybox:latest") { imageTag ->
+                val tempDir = FileSystemUtils.createTempDirectory()
+                try {
+                    val tarball = Path(tempDir, "busybox.tar")
+                    var lastProgress = 0L
+                    var declaredSize: Long? = null
+                    val written =
+                        testClient.images.save(imageTag, tarball.toString()) { bytesReceived, totalBytes ->
+                            lastProgress = bytesReceived
+                            declaredSize = totalBytes
+                        }
+
+                    assertTrue(written > 0, "Saved tarball must not be empty")
+                    assertEquals(written, lastProgress)
+                    declaredSize?.let { size -> assertEquals(written, size) }
+
+                    var sent = 0L
+                    var total: Long? = null
+                    val loaded =
+                        testClient.images.load(ImageLoadSource.File(tarball.toString())) { bytesSent, totalBytes ->
+                            sent = bytesSent
+                            total = totalBytes
+                        }
+
+                    assertEquals(listOf(imageTag), loaded)
+                    assertEquals(written, sent)
+                    assertEquals(written, total)
+                } finally {
+                    FileSystemUtils.deleteRecursively(tempDir)
+                }
+            }
+        }
+
+    @Test
+    fun `throws ImageNotFoundException on save unknown image`() =
+        runTest {
+            val tempDir = FileSystemUtils.createTempDirectory()
+            try {
+                assertFailsWith<ImageNotFoundException> {
+                    testClient.images.save("busybox:billiejean", Path(tempDir, "image.tar").toString())
+                }
+            } finally {
+                FileSystemUtils.deleteRecursively(tempDir)
+            }
+        }
 }
@@ -0,0 +1,69 @@
+package me.devnatan.dockerkt.resource.image
+
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerClient
+import me.devnatan.dockerkt.createTestDockerClient
+import me.devnatan.dockerkt.io.HttpFixtures
+import me.devnatan.dockerkt.io.Interaction
+import me.devnatan.dockerkt.io.RecordedRequest
+import me.devnatan.dockerkt.io.RecordedResponse
+import me.devnatan.dockerkt.io.withCassette
+import me.devnatan.dockerkt.models.image.ImageLoadSource
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class ImageLoadTest {
+    private suspend fun replayLoad(
+        body: String,
+        block: suspend (DockerClient) -> Unit,
+    ) {
+        val interaction =
+            Interaction(
+                RecordedRequest("POST", "/v1.41/images/load?quiet=false"),
+                RecordedResponse(status = 200, headers = mapOf("Content-Type" to listOf("application/json")), body = body),
+            )
+
+        withCassette(interaction) { cassette ->
+            val client =
+                createTestDockerClient {
+                    apiVersion("1.41")
+                    httpFixtures(HttpFixtures.Replay(cassette))
+                }
+
+            try {
+                block(client)
+            } finally {
+                client.close()
+            }
+        }
+    }
+
+    @Test
+    fun `returns the loaded images`() =
+        runTest {
+            replayLoad(
+                """
+                {"stream":"Loaded image: busybox:latest\n"}
+                {"stream":"Loaded image ID: sha256:3f57d9401f8d42f986df300f0c69192fc41da28ccc8d797829467780db3dd741\n"}
+                """.trimIndent(),
+            ) { client ->
+                assertEquals(
+                    listOf("busybox:latest", "sha256:3f57d9401f8d42f986df300f0c69192fc41da28ccc8d797829467780db3dd741"),
+                    client.images.load(ImageLoadSource.Stream { -1 }),
+                )
+            }
+        }
+
+    @Test
+    fun `throws ImageLoadException on load error`() =
+        runTest {
+            replayLoad("""{"errorDetail":{"message":"unexpected EOF"},"error":"unexpected EOF"}""") { client ->
+                val error =
+                    assertFailsWith<ImageLoadException> {
+                        client.images.load(ImageLoadSource.Stream { -1 })
+                    }
+                assertEquals("unexpected EOF", error.message)
+            }
+        }
+}
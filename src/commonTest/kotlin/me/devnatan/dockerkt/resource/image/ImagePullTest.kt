@@ -0,0 +1,48 @@
+package me.devnatan.dockerkt.resource.image
+
+import kotlinx.coroutines.flow.collect
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerDryRunException
+import me.devnatan.dockerkt.createTestDockerClient
+import me.devnatan.dockerkt.models.RegistryAuth
+import kotlin.io.encoding.Base64
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class ImagePullTest {
+    @Test
+    fun `every collection of a pull authenticates with the credentials`() =
+        runTest {
+            val client = createTestDockerClient { dryRun() }
+            try {
+                val pull = client.images.pull("busybox", RegistryAuth.Password("devnatan", "hunter2".toCharArray()))
+
+                repeat(2) {
+                    val exception = assertFailsWith<DockerDryRunException> { pull.collect() }
+                    val header = exception.request.headers["X-Registry-Auth"]?.single()
+
+                    assertEquals(
+                        "{\"username\":\"devnatan\",\"password\":\"hunter2\"}",
+                        header?.let { Base64.UrlSafe.decode(it).decodeToString() },
+                    )
+                }
+            } finally {
+                client.close()
+            }
+        }
+
+    @Test
+    fun `reusing credentials of another pull throws`() =
+        runTest {
+            val client = createTestDockerClient { dryRun() }
+            try {
+                val auth = RegistryAuth.IdentityToken("identity-token".toCharArray())
+                client.images.pull("busybox", auth)
+
+                assertFailsWith<IllegalStateException> { client.images.pull("alpine", auth) }
+            } finally {
+                client.close()
+            }
+        }
+}
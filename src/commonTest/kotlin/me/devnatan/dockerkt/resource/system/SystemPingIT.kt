@@ -27,4 +27,14 @@ class SystemPingIT : ResourceIT() {
                 fail("Failed to ping server (GET).", throwable)
             }
         }
+
+    @Test
+    fun `prewarm connections`() =
+        runTest {
+            runCatching {
+                testClient.prewarm(connections = 2)
+            }.onFailure { throwable ->
+                fail("Failed to prewarm connections.", throwable)
+            }
+        }
 }
@@ -0,0 +1,72 @@
+package me.devnatan.dockerkt
+
+import kotlinx.coroutines.Dispatchers
+import kotlinx.coroutines.withContext
+import me.devnatan.dockerkt.io.createTlsContext
+import java.net.InetAddress
+import java.net.InetSocketAddress
+import java.nio.file.Files
+import java.nio.file.Paths
+import java.security.cert.CertificateException
+import java.security.cert.X509Certificate
+import javax.net.ssl.SSLHandshakeException
+import javax.net.ssl.SSLPeerUnverifiedException
+import javax.net.ssl.SSLSocket
+import kotlin.time.Duration
+
+internal actual fun isReadableAndWritable(path: String): Boolean? {
+    val file = Paths.get(path)
+    return Files.isReadable(file) && Files.isWritable(file)
+}
+
+internal actual suspend fun resolveHostAddresses(host: String): List<String>? =
+    withContext(Dispatchers.IO) {
+        InetAddress.getAllByName(host).mapNotNull { address -> address.hostAddress }
+    }
+
+internal actual suspend fun performTlsHandshake(
+    host: String,
+    port: Int,
+    tls: DockerTlsConfig,
+    connectTimeout: Duration,
+): TlsHandshakeResult? =
+    withContext(Dispatchers.IO) {
+        val tlsContext = tls.createTlsContext()
+        (tlsContext.sslContext.socketFactory.createSocket() as SSLSocket).use { socket ->
+            val timeout = connectTimeout.inWholeMilliseconds.toInt()
+            socket.connect(InetSocketAddress(host, port), timeout)
+            socket.soTimeout = timeout
+            socket.sslParameters =
+                socket.sslParameters.apply {
+                    protocols =
+                        TlsVersion.entries
+                            .filter { version -> version in tls.minVersion..tls.maxVersion }
+                            .map(TlsVersion::protocolName)
+                            .toTypedArray()
+                    tls.cipherSuites?.let { cipherSuites = it.toTypedArray() }
+                    if (!tls.acceptInvalidHostnames) endpointIdentificationAlgorithm = "HTTPS"
+                }
+
+            try {
+                socket.startHandshake()
+            } catch (e: SSLHandshakeException) {
+                val certificateError =
+                    generateSequence<Throwable>(e) { it.cause }
+                        .any { it is CertificateException || it is SSLPeerUnverifiedException }
+                val message = e.message ?: e::class.simpleName.orEmpty()
+                return@withContext if (certificateError) {
+                    TlsHandshakeResult.CertificateRejected(message)
+                } else {
+                    TlsHandshakeResult.NegotiationFailed(message)
+                }
+            }
+
+            val session = socket.session
+            TlsHandshakeResult.Established(
+                protocol = session.protocol,
+                cipherSuite = session.cipherSuite,
+                peerCertificate =
+                    (session.peerCertificates.firstOrNull() as? X509Certificate)?.subjectX500Principal?.name,
+            )
+        }
+    }
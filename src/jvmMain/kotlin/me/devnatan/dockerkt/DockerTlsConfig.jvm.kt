@@ -0,0 +1,12 @@
+package me.devnatan.dockerkt
+
+import java.security.Security
+
+internal actual fun isFipsSecurityProvider(name: String): Boolean {
+    val provider = Security.getProvider(name) ?: return false
+
+    // Bouncy Castle JSSE keeps its name in FIPS mode and only exposes it through this method
+    val fipsMode =
+        runCatching { provider.javaClass.getMethod("isFipsMode").invoke(provider) as? Boolean }.getOrNull()
+    return fipsMode ?: listOf(provider.name, provider.info).any { text -> text.contains("FIPS", ignoreCase = true) }
+}
@@ -0,0 +1,240 @@
+package me.devnatan.dockerkt.io
+
+import io.ktor.client.engine.HttpClientEngine
+import io.ktor.client.engine.HttpClientEngineBase
+import io.ktor.client.engine.HttpClientEngineCapability
+import io.ktor.client.engine.HttpClientEngineConfig
+import io.ktor.client.engine.HttpClientEngineFactory
+import io.ktor.client.engine.okhttp.OkHttp
+import io.ktor.client.engine.okhttp.OkHttpConfig
+import io.ktor.client.request.HttpRequestData
+import io.ktor.client.request.HttpResponseData
+import io.ktor.utils.io.InternalAPI
+import kotlinx.coroutines.asContextElement
+import kotlinx.coroutines.withContext
+import me.devnatan.dockerkt.DockerPeerCertificate
+import me.devnatan.dockerkt.EngineCallInfo
+import me.devnatan.dockerkt.EngineCallInfoKey
+import me.devnatan.dockerkt.MetricsRecorder
+import okhttp3.Call
+import okhttp3.Connection
+import okhttp3.EventListener
+import okhttp3.Handshake
+import okhttp3.Protocol
+import okhttp3.Request
+import okhttp3.Response
+import java.io.IOException
+import java.net.InetAddress
+import java.net.InetSocketAddress
+import java.net.Proxy
+import java.security.cert.X509Certificate
+import kotlin.time.Duration
+import kotlin.time.Duration.Companion.nanoseconds
+import kotlin.time.ExperimentalTime
+import kotlin.time.Instant
+
+// details of the request whose OkHttp call is being created on the current thread, see CallInfoEngine
+private val CurrentCallInfo = ThreadLocal<EngineCallInfo?>()
+
+/**
+ * Records the network events of a single call, as reported by OkHttp.
+ *
+ * @param metrics Metrics of the client the call is made by, DNS lookups and acquired connections are added to.
+ * @param info Details of the call to report the events to once its response arrives, if they're wanted.
+ * @param timings Whether the phases of the call are reported.
+ */
+private class CallEvents(
+    private val metrics: MetricsRecorder,
+    private val info: EngineCallInfo?,
+    private val timings: Boolean,
+) : EventListener() {
+    @Volatile var dnsStart = 0L
+
+    @Volatile var dnsEnd = 0L
+
+    @Volatile var connectStart = 0L
+
+    @Volatile var connectEnd = 0L
+
+    @Volatile var tlsStart = 0L
+
+    @Volatile var tlsEnd = 0L
+
+    @Volatile var requestEnd = 0L
+
+    @Volatile var responseStart = 0L
+
+    @Volatile var remoteAddress: String? = null
+
+    @Volatile var localAddress: String? = null
+
+    @Volatile var connectionReused: Boolean? = null
+
+    // whether a connection was just established by this call, and not acquired yet
+    @Volatile private var connectionEstablished = false
+
+    override fun dnsStart(
+        call: Call,
+        domainName: String,
+    ) {
+        dnsStart = System.nanoTime()
+    }
+
+    override fun dnsEnd(
+        call: Call,
+        domainName: String,
+        inetAddressList: List<InetAddress>,
+    ) {
+        dnsEnd = System.nanoTime()
+        metrics.dnsResolved((dnsEnd - dnsStart) / 1000)
+    }
+
+    override fun connectStart(
+        call: Call,
+        inetSocketAddress: InetSocketAddress,
+        proxy: Proxy,
+    ) {
+        connectStart = System.nanoTime()
+    }
+
+    override fun connectEnd(
+        call: Call,
+        inetSocketAddress: InetSocketAddress,
+        proxy: Proxy,
+        protocol: Protocol?,
+    ) {
+        connectEnd = System.nanoTime()
+        connectionEstablished = true
+    }
+
+    override fun connectFailed(
+        call: Call,
+        inetSocketAddress: InetSocketAddress,
+        proxy: Proxy,
+        protocol: Protocol?,
+        ioe: IOException,
+    ) {
+        connectionEstablished = false
+    }
+
+    override fun secureConnectStart(call: Call) {
+        tlsStart = System.nanoTime()
+    }
+
+    override fun secureConnectEnd(
+        call: Call,
+        handshake: Handshake?,
+    ) {
+        tlsEnd = System.nanoTime()
+    }
+
+    override fun requestHeadersEnd(
+        call: Call,
+        request: Request,
+    ) {
+        requestEnd = System.nanoTime()
+    }
+
+    override fun requestBodyEnd(
+        call: Call,
+        byteCount: Long,
+    ) {
+        requestEnd = System.nanoTime()
+    }
+
+    override fun responseHeadersStart(call: Call) {
+        responseStart = System.nanoTime()
+    }
+
+    override fun connectionAcquired(
+        call: Call,
+        connection: Connection,
+    ) {
+        val socket = connection.socket()
+        remoteAddress = socket.remoteSocketAddress?.toString()
+        localAddress = socket.localSocketAddress?.toString()
+        // a new connection is acquired right after being established, a pooled one without connecting first, which
+        // also holds when retrying on a pooled connection after a failed attempt
+        val reused = !connectionEstablished
+        connectionReused = reused
+        connectionEstablished = false
+        metrics.connectionAcquired(reused)
+    }
+
+    // OkHttp hands the response over to Ktor only after this, so the details are complete once it's received
+    override fun responseHeadersEnd(
+        call: Call,
+        response: Response,
+    ) {
+        val info = info ?: return
+        if (timings) {
+            info.dns = phase(dnsStart, dnsEnd)
+            info.connect = phase(connectStart, connectEnd)
+            info.tls = phase(tlsStart, tlsEnd)
+            info.timeToFirstByte = phase(requestEnd, responseStart)
+        }
+        info.remoteAddress = remoteAddress
+        info.localAddress = localAddress
+        info.connectionReused = connectionReused
+        info.peerCertificate = (response.handshake?.peerCertificates?.firstOrNull() as? X509Certificate)?.toPeerCertificate()
+    }
+
+    private fun phase(
+        start: Long,
+        end: Long,
+    ): Duration? = if (start == 0L || end < start) null else (end - start).nanoseconds
+}
+
+/**
+ * Records the network events of every call into the client [metrics], and reports them to the [EngineCallInfo] of
+ * the request the call is made for, if any.
+ *
+ * @property timings Whether the phases of the calls are measured.
+ */
+internal class CallEventsRecorder(
+    private val metrics: MetricsRecorder,
+    private val timings: Boolean,
+) : EventListener.Factory {
+    // called by OkHttp while the call is created, still within the request coroutine of CallInfoEngine
+    override fun create(call: Call): EventListener = CallEvents(metrics, CurrentCallInfo.get(), timings)
+}
+
+/**
+ * Creates OkHttp engines that hand the [EngineCallInfo] of each request over to [CallEventsRecorder].
+ *
+ * Ktor only passes the method, URL, headers and body of requests to OkHttp, so the details are made available through
+ * a thread local while the OkHttp call is created instead of being sent along with the request.
+ */
+internal object CallInfoOkHttp : HttpClientEngineFactory<OkHttpConfig> {
+    override fun create(block: OkHttpConfig.() -> Unit): HttpClientEngine = CallInfoEngine(OkHttp.create(block))
+}
+
+private class CallInfoEngine(
+    private val delegate: HttpClientEngine,
+) : HttpClientEngineBase("docker-kotlin-okhttp") {
+    override val config: HttpClientEngineConfig get() = delegate.config
+
+    override val supportedCapabilities: Set<HttpClientEngineCapability<*>> get() = delegate.supportedCapabilities
+
+    @OptIn(InternalAPI::class)
+    override suspend fun execute(data: HttpRequestData): HttpResponseData {
+        val info = data.attributes.getOrNull(EngineCallInfoKey) ?: return delegate.execute(data)
+        return withContext(CurrentCallInfo.asContextElement(info)) { delegate.execute(data) }
+    }
+
+    override fun close() {
+        super.close()
+        delegate.close()
+    }
+}
+
+@OptIn(ExperimentalTime::class)
+private fun X509Certificate.toPeerCertificate(): DockerPeerCertificate =
+    DockerPeerCertificate(
+        subject = subjectX500Principal.name,
+        issuer = issuerX500Principal.name,
+        // DNS names and IP addresses are the only alternative names held as strings
+        subjectAlternativeNames = subjectAlternativeNames?.mapNotNull { name -> name.getOrNull(1) as? String }.orEmpty(),
+        notBefore = Instant.fromEpochMilliseconds(notBefore.time),
+        notAfter = Instant.fromEpochMilliseconds(notAfter.time),
+    )
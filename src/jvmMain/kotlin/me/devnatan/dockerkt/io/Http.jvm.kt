@@ -3,12 +3,20 @@ package me.devnatan.dockerkt.io
 import io.ktor.client.HttpClientConfig
 import io.ktor.client.engine.HttpClientEngineConfig
 import io.ktor.client.engine.HttpClientEngineFactory
-import io.ktor.client.engine.okhttp.OkHttp
 import io.ktor.client.engine.okhttp.OkHttpConfig
 import me.devnatan.dockerkt.DockerClient
+import okhttp3.Dispatcher
 import okhttp3.Interceptor
+import okhttp3.Protocol
 import okhttp3.Response
+import java.net.InetSocketAddress
+import java.net.Proxy
+import java.util.concurrent.SynchronousQueue
+import java.util.concurrent.ThreadFactory
+import java.util.concurrent.ThreadPoolExecutor
 import java.util.concurrent.TimeUnit
+import java.util.concurrent.atomic.AtomicInteger
+import javax.net.SocketFactory
 
 // Ktor doesn't allow us to change "Upgrade" header so we set it directly in the engine
 private class UpgradeHeaderInterceptor : Interceptor {
@@ -36,7 +44,21 @@ private class UpgradeHeaderInterceptor : Interceptor {
     }
 }
 
-internal actual val defaultHttpClientEngine: HttpClientEngineFactory<*>? get() = OkHttp
+// names threads so that they can be told apart in profilers and thread dumps of the embedding application
+private class IoThreadFactory : ThreadFactory {
+    private val counter = AtomicInteger()
+
+    override fun newThread(runnable: Runnable): Thread =
+        Thread(runnable, "docker-kotlin-io-${counter.getAndIncrement()}").apply { isDaemon = true }
+}
+
+// same as the OkHttp default dispatcher, but with named threads
+private fun createDispatcher(): Dispatcher =
+    Dispatcher(ThreadPoolExecutor(0, Int.MAX_VALUE, 60, TimeUnit.SECONDS, SynchronousQueue(), IoThreadFactory()))
+
+internal actual val defaultHttpClientEngine: HttpClientEngineFactory<*>? get() = CallInfoOkHttp
+
+internal actual val engineReportsCallInfo: Boolean get() = true
 
 internal actual fun <T : HttpClientEngineConfig> HttpClientConfig<out T>.configureHttpClient(client: DockerClient) {
     engine {
@@ -44,17 +66,63 @@ internal actual fun <T : HttpClientEngineConfig> HttpClientConfig<out T>.configu
         // https://youtrack.jetbrains.com/issue/KTOR-2494
         require(this is OkHttpConfig) { "Only OkHttp engine is supported for now" }
 
+        // OkHttp clients are created lazily, so key material is loaded now to allow credentials to be wiped after
+        val tlsContext = client.config.tls?.createTlsContext()
+
         config {
-            val isUnixSocket = isUnixSocket(client.config.socketPath)
-            if (isUnixSocket) {
-                socketFactory(UnixSocketFactory())
+            val socketPath = client.config.socketPath
+            val endpoint = DockerEndpoint.parse(socketPath)
+            val socketOptions = client.config.socketOptions ?: SocketOptions()
+            val proxy = client.config.proxy.resolveFor(socketPath)
+            val isSocksProxy = proxy != null && proxy.type != ProxyType.Http
+            when {
+                endpoint is DockerEndpoint.Unix ->
+                    socketFactory(OptionsSocketFactory(UnixSocketFactory(), socketOptions, tcp = false))
+                endpoint is DockerEndpoint.NamedPipe -> socketFactory(NamedPipeSocketFactory(endpoint.path))
+                isSocksProxy -> socketFactory(OptionsSocketFactory(Socks5SocketFactory(proxy), socketOptions))
+                else -> socketFactory(OptionsSocketFactory(SocketFactory.getDefault(), socketOptions))
             }
-            dns(SocketDns(isUnixSocket))
-            readTimeout(0, TimeUnit.MILLISECONDS)
-            connectTimeout(0, TimeUnit.MILLISECONDS)
-            callTimeout(0, TimeUnit.MILLISECONDS)
+            dispatcher(createDispatcher())
+            // hostnames are not resolved locally when the SOCKS proxy resolves them by itself
+            val isLocalSocket = endpoint is DockerEndpoint.Unix || endpoint is DockerEndpoint.NamedPipe
+            dns(SocketDns(isLocalSocket || proxy?.type == ProxyType.Socks5h))
+            // OkHttp also treats zero as no timeout
+            val timeouts = client.config.timeouts
+            readTimeout(timeouts.readIdle.inWholeMilliseconds, TimeUnit.MILLISECONDS)
+            connectTimeout(timeouts.connect.inWholeMilliseconds, TimeUnit.MILLISECONDS)
+            callTimeout(timeouts.total.inWholeMilliseconds, TimeUnit.MILLISECONDS)
             retryOnConnectionFailure(true)
+            if (client.config.forceHttp1) {
+                protocols(listOf(Protocol.HTTP_1_1))
+            }
             addInterceptor(UpgradeHeaderInterceptor())
+            if (endpoint is DockerEndpoint.Unix) {
+                addInterceptor(UnixSocketErrorInterceptor(endpoint.path))
+            }
+            eventListenerFactory(CallEventsRecorder(client.metricsRecorder, client.config.callTimings))
+
+            if (proxy != null && proxy.type == ProxyType.Http) {
+                proxy(Proxy(Proxy.Type.HTTP, InetSocketAddress.createUnresolved(proxy.host, proxy.port)))
+                proxy.basicAuthorization()?.let { authorization ->
+                    proxyAuthenticator { _, response ->
+                        // gives up if the proxy rejected the credentials already
+                        if (response.request.header("Proxy-Authorization") != null) {
+                            null
+                        } else {
+                            response.request
+                                .newBuilder()
+                                .header("Proxy-Authorization", authorization)
+                                .build()
+                        }
+                    }
+                }
+            }
+
+            if (tlsContext != null) {
+                sslSocketFactory(tlsContext.sslContext.socketFactory, tlsContext.trustManager)
+                connectionSpecs(listOf(tlsContext.connectionSpec))
+                tlsContext.hostnameVerifier?.let(::hostnameVerifier)
+            }
         }
     }
 }
@@ -0,0 +1,209 @@
+package me.devnatan.dockerkt.io
+
+import java.io.IOException
+import java.io.InputStream
+import java.io.OutputStream
+import java.net.InetAddress
+import java.net.InetSocketAddress
+import java.net.Socket
+import java.net.SocketAddress
+import java.net.SocketTimeoutException
+import java.nio.ByteBuffer
+import java.nio.channels.AsynchronousFileChannel
+import java.nio.file.FileSystemException
+import java.nio.file.NoSuchFileException
+import java.nio.file.Paths
+import java.nio.file.StandardOpenOption
+import java.util.concurrent.ExecutionException
+import java.util.concurrent.Future
+import java.util.concurrent.TimeUnit
+import java.util.concurrent.TimeoutException
+import javax.net.SocketFactory
+
+// same as go-winio, which the Docker CLI connects to named pipes with, when no connect timeout is set
+private const val DefaultPipeBusyTimeoutMillis = 2000L
+private const val InitialPipeBusyBackoffMillis = 10L
+private const val MaxPipeBusyBackoffMillis = 250L
+
+private fun isWindowsPlatform(): Boolean = System.getProperty("os.name").lowercase().contains("win")
+
+/**
+ * Socket factory that connects to a Windows named pipe, like the `\\.\pipe\docker_engine` pipe Docker Desktop
+ * exposes the Docker daemon at.
+ */
+internal class NamedPipeSocketFactory(
+    private val path: String,
+) : SocketFactory() {
+    init {
+        if (!isWindowsPlatform()) {
+            throw UnsupportedOperationException("Named pipes are only supported on Windows, cannot connect to $path")
+        }
+    }
+
+    override fun createSocket(): Socket = NamedPipeSocket(path)
+
+    override fun createSocket(
+        host: String,
+        port: Int,
+    ): Socket = connectedSocket()
+
+    override fun createSocket(
+        host: String,
+        port: Int,
+        localHost: InetAddress,
+        localPort: Int,
+    ): Socket = connectedSocket()
+
+    override fun createSocket(
+        host: InetAddress,
+        port: Int,
+    ): Socket = connectedSocket()
+
+    override fun createSocket(
+        address: InetAddress,
+        port: Int,
+        localAddress: InetAddress,
+        localPort: Int,
+    ): Socket = connectedSocket()
+
+    private fun connectedSocket(): Socket = createSocket().apply { connect(InetSocketAddress(0)) }
+}
+
+/**
+ * Socket that performs I/O over a Windows named pipe. Overlapped I/O is used so that reads, which block while the
+ * Docker daemon is streaming, don't block writes to the same pipe.
+ */
+private class NamedPipeSocket(
+    private val path: String,
+) : Socket() {
+    @Volatile
+    private var channel: AsynchronousFileChannel? = null
+
+    @Volatile
+    private var closed = false
+
+    @Volatile
+    private var readTimeoutMillis = 0
+
+    override fun connect(endpoint: SocketAddress?) {
+        connect(endpoint, 0)
+    }
+
+    /**
+     * Opens the pipe, waiting up to [timeout] milliseconds for an instance of it to be free if all of them are busy
+     * serving other clients, or two seconds if [timeout] is `0`.
+     */
+    override fun connect(
+        endpoint: SocketAddress?,
+        timeout: Int,
+    ) {
+        val timeoutMillis = if (timeout > 0) timeout.toLong() else DefaultPipeBusyTimeoutMillis
+        val deadline = System.nanoTime() + TimeUnit.MILLISECONDS.toNanos(timeoutMillis)
+        var backoff = InitialPipeBusyBackoffMillis
+        while (true) {
+            try {
+                channel =
+                    AsynchronousFileChannel.open(Paths.get(path), StandardOpenOption.READ, StandardOpenOption.WRITE)
+                return
+            } catch (e: NoSuchFileException) {
+                throw IOException("Named pipe $path does not exist, is the Docker daemon running?", e)
+            } catch (e: FileSystemException) {
+                // Java doesn't expose the Windows error code, but missing pipes and denied access have their own
+                // exception types, so a plain one is most likely ERROR_PIPE_BUSY: all pipe instances are serving
+                // other clients. Any other failure is still reported, as the cause, once the timeout elapses
+                if (e.javaClass != FileSystemException::class.java) throw e
+
+                val remaining = TimeUnit.NANOSECONDS.toMillis(deadline - System.nanoTime())
+                if (remaining <= 0) {
+                    throw SocketTimeoutException("Timed out waiting for a free instance of named pipe $path").apply { initCause(e) }
+                }
+
+                try {
+                    Thread.sleep(backoff.coerceAtMost(remaining))
+                } catch (interrupted: InterruptedException) {
+                    Thread.currentThread().interrupt()
+                    throw IOException("Interrupted while waiting for named pipe $path", interrupted)
+                }
+                backoff = (backoff * 2).coerceAtMost(MaxPipeBusyBackoffMillis)
+            }
+        }
+    }
+
+    override fun isConnected(): Boolean = channel != null
+
+    override fun isClosed(): Boolean = closed
+
+    override fun isInputShutdown(): Boolean = false
+
+    override fun isOutputShutdown(): Boolean = false
+
+    // a read that times out can't be taken back from the pipe, so the pipe is closed afterward, see await
+    override fun setSoTimeout(timeout: Int) {
+        require(timeout >= 0) { "Timeout cannot be negative: $timeout" }
+        readTimeoutMillis = timeout
+    }
+
+    override fun getSoTimeout(): Int = readTimeoutMillis
+
+    override fun getInputStream(): InputStream =
+        object : InputStream() {
+            override fun read(): Int {
+                val byte = ByteArray(1)
+                return if (read(byte, 0, 1) == -1) -1 else byte[0].toInt() and 0xff
+            }
+
+            override fun read(
+                buffer: ByteArray,
+                offset: Int,
+                length: Int,
+            ): Int {
+                if (length == 0) return 0
+                return await(requireChannel().read(ByteBuffer.wrap(buffer, offset, length), 0), readTimeoutMillis)
+            }
+        }
+
+    override fun getOutputStream(): OutputStream =
+        object : OutputStream() {
+            override fun write(byte: Int) {
+                write(byteArrayOf(byte.toByte()), 0, 1)
+            }
+
+            override fun write(
+                buffer: ByteArray,
+                offset: Int,
+                length: Int,
+            ) {
+                val source = ByteBuffer.wrap(buffer, offset, length)
+                while (source.hasRemaining()) {
+                    await(requireChannel().write(source, 0), timeoutMillis = 0)
+                }
+            }
+        }
+
+    override fun close() {
+        closed = true
+        channel?.close()
+    }
+
+    private fun requireChannel(): AsynchronousFileChannel {
+        if (closed) throw IOException("Named pipe $path is closed")
+        return channel ?: throw IOException("Named pipe $path is not connected")
+    }
+
+    private fun await(
+        operation: Future<Int>,
+        timeoutMillis: Int,
+    ): Int =
+        try {
+            if (timeoutMillis > 0) operation.get(timeoutMillis.toLong(), TimeUnit.MILLISECONDS) else operation.get()
+        } catch (e: TimeoutException) {
+            operation.cancel(true)
+            close()
+            throw SocketTimeoutException("Read timed out on named pipe $path").apply { initCause(e) }
+        } catch (e: ExecutionException) {
+            throw e.cause as? IOException ?: IOException(e.cause)
+        } catch (e: InterruptedException) {
+            Thread.currentThread().interrupt()
+            throw IOException("Interrupted while doing I/O on named pipe $path", e)
+        }
+}
@@ -0,0 +1,68 @@
+package me.devnatan.dockerkt.io
+
+import jdk.net.ExtendedSocketOptions
+import java.net.InetAddress
+import java.net.Socket
+import java.net.SocketOption
+import javax.net.SocketFactory
+
+/**
+ * Socket factory that applies [SocketOptions] to every socket created by [delegate].
+ *
+ * @param tcp Whether [delegate] creates TCP sockets, TCP-only options are not applied otherwise.
+ */
+internal class OptionsSocketFactory(
+    private val delegate: SocketFactory,
+    private val options: SocketOptions,
+    private val tcp: Boolean = true,
+) : SocketFactory() {
+    override fun createSocket(): Socket = delegate.createSocket().applyOptions()
+
+    override fun createSocket(
+        host: String,
+        port: Int,
+    ): Socket = delegate.createSocket(host, port).applyOptions()
+
+    override fun createSocket(
+        host: String,
+        port: Int,
+        localHost: InetAddress,
+        localPort: Int,
+    ): Socket = delegate.createSocket(host, port, localHost, localPort).applyOptions()
+
+    override fun createSocket(
+        host: InetAddress,
+        port: Int,
+    ): Socket = delegate.createSocket(host, port).applyOptions()
+
+    override fun createSocket(
+        address: InetAddress,
+        port: Int,
+        localAddress: InetAddress,
+        localPort: Int,
+    ): Socket = delegate.createSocket(address, port, localAddress, localPort).applyOptions()
+
+    private fun Socket.applyOptions(): Socket {
+        options.receiveBufferSize?.let(::setReceiveBufferSize)
+        options.sendBufferSize?.let(::setSendBufferSize)
+        if (!tcp) return this
+
+        tcpNoDelay = options.tcpNoDelay
+        options.keepAlive?.let(::setKeepAlive)
+
+        // fine-grained keepalive options are not available on every operating system
+        setOptionIfSupported(ExtendedSocketOptions.TCP_KEEPIDLE, options.keepAliveIdle?.inWholeSeconds?.toInt())
+        setOptionIfSupported(ExtendedSocketOptions.TCP_KEEPINTERVAL, options.keepAliveInterval?.inWholeSeconds?.toInt())
+        setOptionIfSupported(ExtendedSocketOptions.TCP_KEEPCOUNT, options.keepAliveCount)
+        return this
+    }
+
+    private fun <T> Socket.setOptionIfSupported(
+        option: SocketOption<T>,
+        value: T?,
+    ) {
+        if (value != null && option in supportedOptions()) {
+            setOption(option, value)
+        }
+    }
+}
@@ -1,10 +1,14 @@
 package me.devnatan.dockerkt.io
 
 import okhttp3.Dns
+import okhttp3.Interceptor
+import okhttp3.Response
 import org.newsclub.net.unix.AFUNIXSocketAddress
 import org.newsclub.net.unix.AFUNIXSocketFactory
+import java.io.IOException
 import java.net.InetAddress
-import java.nio.file.Files
+import java.nio.file.AccessDeniedException
+import java.nio.file.NoSuchFileException
 import java.nio.file.Paths
 
 internal class SocketDns(
@@ -30,11 +34,35 @@ internal class UnixSocketFactory : AFUNIXSocketFactory() {
     ): AFUNIXSocketAddress {
         val socketPath = decodeSocketPathHostname(host)
         val socketFile = Paths.get(socketPath) ?: error("Unable to connect to unix socket @ $socketPath")
+        return AFUNIXSocketAddress.of(socketFile)
+    }
+}
 
-        if (!Files.exists(socketFile) || !Files.isWritable(socketFile)) {
-            error("Socket path do not exists or is not writable: $socketFile")
+/**
+ * Reports the failures to connect to the unix socket at [socketPath] as [DockerSocketException]s, based on the error
+ * the connection failed with.
+ */
+internal class UnixSocketErrorInterceptor(
+    private val socketPath: String,
+) : Interceptor {
+    override fun intercept(chain: Interceptor.Chain): Response =
+        try {
+            chain.proceed(chain.request())
+        } catch (e: IOException) {
+            if (e is DockerSocketException) throw e
+            val reason = unixSocketFailureReason(e) ?: throw e
+            throw DockerSocketException(socketPath, reason, e)
         }
+}
 
-        return AFUNIXSocketAddress.of(socketFile)
+// the messages are the ones of strerror, reported by both the JDK and junixsocket
+internal fun unixSocketFailureReason(error: Throwable): DockerSocketException.Reason? =
+    generateSequence(error, Throwable::cause).firstNotNullOfOrNull { cause ->
+        val message = cause.message.orEmpty()
+        when {
+            cause is NoSuchFileException || "No such file or directory" in message -> DockerSocketException.Reason.NotFound
+            cause is AccessDeniedException || "Permission denied" in message -> DockerSocketException.Reason.PermissionDenied
+            "Connection refused" in message -> DockerSocketException.Reason.ConnectionRefused
+            else -> null
+        }
     }
-}
@@ -0,0 +1,179 @@
+package me.devnatan.dockerkt.io
+
+import java.io.DataInputStream
+import java.io.IOException
+import java.io.OutputStream
+import java.net.InetAddress
+import java.net.InetSocketAddress
+import java.net.Socket
+import java.net.SocketAddress
+import javax.net.SocketFactory
+
+private const val SocksVersion = 5
+private const val NoAuthenticationMethod = 0x00
+private const val UsernamePasswordMethod = 0x02
+private const val NoAcceptableMethods = 0xff
+private const val UsernamePasswordVersion = 1
+private const val ConnectCommand = 1
+private const val Ipv4AddressType = 1
+private const val DomainNameAddressType = 3
+private const val Ipv6AddressType = 4
+
+// RFC 1928 reply codes
+private val SocksReplyMessages =
+    mapOf(
+        1 to "general SOCKS server failure",
+        2 to "connection not allowed by ruleset",
+        3 to "network unreachable",
+        4 to "host unreachable",
+        5 to "connection refused",
+        6 to "TTL expired",
+        7 to "command not supported",
+        8 to "address type not supported",
+    )
+
+/**
+ * Socket factory whose sockets connect through a SOCKS5 proxy, authenticating with the proxy credentials if any.
+ *
+ * The JDK SOCKS support reads credentials from the JVM-wide authenticator, so the handshake is done here instead to
+ * keep credentials per client.
+ */
+internal class Socks5SocketFactory(
+    private val proxy: ProxyConfig,
+) : SocketFactory() {
+    override fun createSocket(): Socket = Socks5Socket(proxy)
+
+    override fun createSocket(
+        host: String,
+        port: Int,
+    ): Socket = createSocket().apply { connect(InetSocketAddress.createUnresolved(host, port)) }
+
+    override fun createSocket(
+        host: String,
+        port: Int,
+        localHost: InetAddress,
+        localPort: Int,
+    ): Socket =
+        createSocket().apply {
+            bind(InetSocketAddress(localHost, localPort))
+            connect(InetSocketAddress.createUnresolved(host, port))
+        }
+
+    override fun createSocket(
+        host: InetAddress,
+        port: Int,
+    ): Socket = createSocket().apply { connect(InetSocketAddress(host, port)) }
+
+    override fun createSocket(
+        address: InetAddress,
+        port: Int,
+        localAddress: InetAddress,
+        localPort: Int,
+    ): Socket =
+        createSocket().apply {
+            bind(InetSocketAddress(localAddress, localPort))
+            connect(InetSocketAddress(address, port))
+        }
+}
+
+private class Socks5Socket(
+    private val proxy: ProxyConfig,
+) : Socket() {
+    override fun connect(
+        endpoint: SocketAddress,
+        timeout: Int,
+    ) {
+        require(endpoint is InetSocketAddress) { "Unsupported address type: ${endpoint::class}" }
+
+        super.connect(InetSocketAddress(proxy.host, proxy.port), timeout)
+        val readTimeout = soTimeout
+        try {
+            // bounds the handshake by the connect timeout, so a proxy that never answers doesn't block forever
+            soTimeout = timeout
+            handshake(endpoint)
+            soTimeout = readTimeout
+        } catch (e: IOException) {
+            close()
+            throw e
+        }
+    }
+
+    private fun handshake(endpoint: InetSocketAddress) {
+        val output = getOutputStream()
+        val input = DataInputStream(getInputStream())
+
+        val username = proxy.username
+        val methods = if (username != null) listOf(NoAuthenticationMethod, UsernamePasswordMethod) else listOf(NoAuthenticationMethod)
+        output.write(byteArrayOf(SocksVersion.toByte(), methods.size.toByte()) + methods.map(Int::toByte))
+        output.flush()
+
+        checkVersion(input.readUnsignedByte())
+        when (val method = input.readUnsignedByte()) {
+            NoAuthenticationMethod -> {}
+            UsernamePasswordMethod -> authenticate(output, input, username ?: throw IOException("SOCKS proxy requires authentication"))
+            NoAcceptableMethods -> throw IOException("SOCKS proxy accepts none of the offered authentication methods")
+            else -> throw IOException("SOCKS proxy chose an unsupported authentication method: $method")
+        }
+
+        // the hostname is sent when it wasn't resolved locally, so that the proxy resolves it
+        val address = endpoint.address
+        val destination =
+            if (address == null || proxy.type == ProxyType.Socks5h) {
+                val host = endpoint.hostString.encodeToByteArray()
+                if (host.size > 255) throw IOException("Hostname too long for a SOCKS proxy: ${endpoint.hostString}")
+                byteArrayOf(DomainNameAddressType.toByte(), host.size.toByte()) + host
+            } else {
+                val type = if (address.address.size == 4) Ipv4AddressType else Ipv6AddressType
+                byteArrayOf(type.toByte()) + address.address
+            }
+        val port = byteArrayOf((endpoint.port shr 8).toByte(), endpoint.port.toByte())
+        output.write(byteArrayOf(SocksVersion.toByte(), ConnectCommand.toByte(), 0) + destination + port)
+        output.flush()
+
+        checkVersion(input.readUnsignedByte())
+        val reply = input.readUnsignedByte()
+        if (reply != 0) {
+            val message = SocksReplyMessages[reply] ?: "unknown error $reply"
+            throw IOException("SOCKS proxy failed to connect to ${endpoint.hostString}:${endpoint.port}: $message")
+        }
+
+        // skips the reserved byte, the bound address and port
+        input.readUnsignedByte()
+        val boundAddressSize =
+            when (val type = input.readUnsignedByte()) {
+                Ipv4AddressType -> 4
+                Ipv6AddressType -> 16
+                DomainNameAddressType -> input.readUnsignedByte()
+                else -> throw IOException("SOCKS proxy replied with an unsupported address type: $type")
+            }
+        input.readFully(ByteArray(boundAddressSize + 2))
+    }
+
+    private fun authenticate(
+        output: OutputStream,
+        input: DataInputStream,
+        username: String,
+    ) {
+        val user = username.encodeToByteArray()
+        val password = proxy.password.orEmpty().encodeToByteArray()
+        if (user.size > 255 || password.size > 255) {
+            throw IOException("SOCKS proxy credentials cannot be longer than 255 bytes")
+        }
+
+        output.write(
+            byteArrayOf(UsernamePasswordVersion.toByte(), user.size.toByte()) + user + password.size.toByte() + password,
+        )
+        output.flush()
+
+        input.readUnsignedByte()
+        if (input.readUnsignedByte() != 0) {
+            throw IOException("SOCKS proxy rejected the credentials of $username")
+        }
+    }
+
+    private fun checkVersion(version: Int) {
+        if (version != SocksVersion) {
+            throw IOException("Not a SOCKS5 proxy, replied with version $version")
+        }
+    }
+}
@@ -0,0 +1,470 @@
+package me.devnatan.dockerkt.io
+
+import me.devnatan.dockerkt.ClientIdentity
+import me.devnatan.dockerkt.DockerTlsConfig
+import me.devnatan.dockerkt.TlsVersion
+import me.devnatan.dockerkt.TrustStore
+import okhttp3.ConnectionSpec
+import java.io.ByteArrayOutputStream
+import java.nio.ByteBuffer
+import java.nio.file.Files
+import java.nio.file.Paths
+import java.security.KeyFactory
+import java.security.KeyStore
+import java.security.MessageDigest
+import java.security.PrivateKey
+import java.security.Security
+import java.security.cert.Certificate
+import java.security.cert.CertificateException
+import java.security.cert.CertificateFactory
+import java.security.cert.X509Certificate
+import java.security.spec.PKCS8EncodedKeySpec
+import java.util.Base64
+import javax.net.ssl.HostnameVerifier
+import javax.net.ssl.KeyManager
+import javax.net.ssl.KeyManagerFactory
+import javax.net.ssl.SSLContext
+import javax.net.ssl.TrustManagerFactory
+import javax.net.ssl.X509TrustManager
+
+// CA bundles of the most common Linux distributions
+private val LinuxCaBundlePaths =
+    listOf(
+        "/etc/ssl/certs/ca-certificates.crt",
+        "/etc/pki/tls/certs/ca-bundle.crt",
+        "/etc/ssl/ca-bundle.pem",
+        "/etc/pki/ca-trust/extracted/pem/tls-ca-bundle.pem",
+        "/etc/ssl/cert.pem",
+    )
+
+// AlgorithmIdentifier of rsaEncryption (1.2.840.113549.1.1.1) with NULL parameters, DER encoded
+private val RsaAlgorithmIdentifier =
+    byteArrayOf(
+        0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86.toByte(), 0x48, 0x86.toByte(), 0xf7.toByte(), 0x0d, 0x01, 0x01, 0x01, 0x05,
+        0x00,
+    )
+
+private val PemBeginMarker = "-----BEGIN ".encodeToByteArray()
+private val PemDashes = "-----".encodeToByteArray()
+
+/**
+ * Looks security services up in the provider named [name], or in the runtime defaults if `null`, so that keys,
+ * certificates and digests are handled by the configured provider too, not only the TLS protocol.
+ */
+internal class SecurityServices(
+    private val name: String?,
+) {
+    init {
+        name?.let { provider ->
+            requireNotNull(Security.getProvider(provider)) { "Security provider \"$provider\" is not registered" }
+        }
+    }
+
+    fun sslContext(): SSLContext = name?.let { SSLContext.getInstance("TLS", it) } ?: SSLContext.getInstance("TLS")
+
+    fun trustManagerFactory(): TrustManagerFactory {
+        val algorithm = TrustManagerFactory.getDefaultAlgorithm()
+        return name?.let { TrustManagerFactory.getInstance(algorithm, it) } ?: TrustManagerFactory.getInstance(algorithm)
+    }
+
+    fun keyManagerFactory(): KeyManagerFactory {
+        val algorithm = KeyManagerFactory.getDefaultAlgorithm()
+        return name?.let { KeyManagerFactory.getInstance(algorithm, it) } ?: KeyManagerFactory.getInstance(algorithm)
+    }
+
+    fun keyStore(type: String): KeyStore = name?.let { KeyStore.getInstance(type, it) } ?: KeyStore.getInstance(type)
+
+    fun keyFactory(algorithm: String): KeyFactory =
+        name?.let { KeyFactory.getInstance(algorithm, it) } ?: KeyFactory.getInstance(algorithm)
+
+    fun certificateFactory(): CertificateFactory =
+        name?.let { CertificateFactory.getInstance("X.509", it) } ?: CertificateFactory.getInstance("X.509")
+
+    fun messageDigest(algorithm: String): MessageDigest =
+        name?.let { MessageDigest.getInstance(algorithm, it) } ?: MessageDigest.getInstance(algorithm)
+}
+
+internal class TlsContext(
+    val sslContext: SSLContext,
+    val trustManager: X509TrustManager,
+    val connectionSpec: ConnectionSpec,
+    val hostnameVerifier: HostnameVerifier?,
+)
+
+// trusts every certificate, for DockerTlsConfig.acceptInvalidCertificates
+private object InsecureTrustManager : X509TrustManager {
+    override fun checkClientTrusted(
+        chain: Array<out X509Certificate>,
+        authType: String,
+    ) = Unit
+
+    override fun checkServerTrusted(
+        chain: Array<out X509Certificate>,
+        authType: String,
+    ) = Unit
+
+    override fun getAcceptedIssuers(): Array<X509Certificate> = emptyArray()
+}
+
+/**
+ * Rejects server certificate chains trusted by [delegate] that don't contain one of the [pins] public keys.
+ *
+ * Pins are checked here rather than by OkHttp's certificate pinner, which needs the trusted roots to rebuild the
+ * chain and so fails every handshake when invalid certificates are accepted.
+ */
+private class PinningTrustManager(
+    private val delegate: X509TrustManager,
+    private val pins: List<String>,
+    private val services: SecurityServices,
+) : X509TrustManager by delegate {
+    override fun checkServerTrusted(
+        chain: Array<out X509Certificate>,
+        authType: String,
+    ) {
+        delegate.checkServerTrusted(chain, authType)
+
+        val keys = verifiedChain(chain).map { certificate -> certificate.publicKeyPin(services) }
+        if (keys.none(pins::contains)) {
+            throw CertificateException(
+                "Docker daemon public key pinning failure, got ${keys.joinToString()} but expected one of ${pins.joinToString()}",
+            )
+        }
+    }
+
+    // only certificates actually signing the leaf certificate count, since any certificate can be appended to a chain
+    private fun verifiedChain(chain: Array<out X509Certificate>): List<X509Certificate> {
+        val candidates = chain.drop(1) + delegate.acceptedIssuers
+        val verified = mutableListOf(chain.first())
+        while (true) {
+            val last = verified.last()
+            verified +=
+                candidates.firstOrNull { candidate ->
+                    candidate !in verified &&
+                        candidate.subjectX500Principal == last.issuerX500Principal &&
+                        runCatching { last.verify(candidate.publicKey) }.isSuccess
+                } ?: return verified
+        }
+    }
+}
+
+private fun X509Certificate.publicKeyPin(services: SecurityServices): String =
+    "sha256/" + Base64.getEncoder().encodeToString(services.messageDigest("SHA-256").digest(publicKey.encoded))
+
+internal fun DockerTlsConfig.createTlsContext(): TlsContext {
+    val services = SecurityServices(securityProvider)
+    val trustManager =
+        (if (acceptInvalidCertificates) InsecureTrustManager else createTrustManager(services)).let { trustManager ->
+            if (pinnedPublicKeys.isEmpty()) {
+                trustManager
+            } else {
+                PinningTrustManager(trustManager, pinnedPublicKeys, services)
+            }
+        }
+    val sslContext = services.sslContext()
+    sslContext.init(clientIdentity?.createKeyManagers(services), arrayOf(trustManager), null)
+
+    // sessions are cached per SSLContext, so connections of the same client resume them automatically
+    sslContext.clientSessionContext.apply {
+        this@createTlsContext.sessionCacheSize?.let { size -> sessionCacheSize = size }
+        // longer timeouts, up to an infinite one, are capped to the longest one the runtime accepts
+        this@createTlsContext.sessionTimeout?.let { timeout ->
+            sessionTimeout = timeout.inWholeSeconds.coerceIn(0, Int.MAX_VALUE.toLong()).toInt()
+        }
+    }
+
+    val hostnameVerifier = if (acceptInvalidHostnames) HostnameVerifier { _, _ -> true } else null
+    return TlsContext(sslContext, trustManager, createConnectionSpec(), hostnameVerifier)
+}
+
+private fun DockerTlsConfig.createTrustManager(services: SecurityServices): X509TrustManager {
+    val trustManagerFactory = services.trustManagerFactory()
+    trustManagerFactory.init(
+        when {
+            caCertificatePath != null -> loadCaTrustStore(readCertificates(caCertificatePath, services), services)
+            caCertificates != null ->
+                loadCaTrustStore(parseCertificates(caCertificates, "in-memory CA certificates", services), services)
+            trustStore == TrustStore.Bundled -> null
+            else -> loadSystemTrustStore(services)
+        },
+    )
+
+    return trustManagerFactory.trustManagers.filterIsInstance<X509TrustManager>().firstOrNull()
+        ?: error("No X509 trust manager available")
+}
+
+/**
+ * Creates the OkHttp connection spec restricting the negotiated TLS versions and cipher suites.
+ */
+internal fun DockerTlsConfig.createConnectionSpec(): ConnectionSpec =
+    ConnectionSpec
+        .Builder(ConnectionSpec.MODERN_TLS)
+        .tlsVersions(
+            *TlsVersion.entries
+                .filter { version -> version in minVersion..maxVersion }
+                .map(TlsVersion::protocolName)
+                .toTypedArray(),
+        ).apply {
+            cipherSuites?.let { cipherSuites(*it.toTypedArray()) }
+        }.build()
+
+private fun ClientIdentity.createKeyManagers(services: SecurityServices): Array<KeyManager> =
+    when (this) {
+        is ClientIdentity.Pkcs12 -> {
+            val file = Paths.get(path)
+            require(Files.isReadable(file)) { "PKCS#12 bundle not found or not readable: $path" }
+
+            val keyStore = services.keyStore("PKCS12")
+            Files.newInputStream(file).use { input -> keyStore.load(input, password) }
+
+            val keyManagerFactory = services.keyManagerFactory()
+            keyManagerFactory.init(keyStore, password)
+            keyManagerFactory.keyManagers
+        }
+        is ClientIdentity.Pem ->
+            createKeyManagers(
+                readCertificates(certificatePath, services),
+                readPrivateKey(privateKeyPath, services),
+                services,
+            )
+        is ClientIdentity.PemData ->
+            createKeyManagers(
+                parseCertificates(certificate, "in-memory client certificate", services),
+                parsePrivateKey(privateKey.copyOf(), "in-memory client private key", services),
+                services,
+            )
+    }
+
+private fun createKeyManagers(
+    certificates: List<Certificate>,
+    privateKey: PrivateKey,
+    services: SecurityServices,
+): Array<KeyManager> {
+    val password = CharArray(0)
+
+    val keyStore = services.keyStore("PKCS12")
+    keyStore.load(null, null)
+    keyStore.setKeyEntry("client", privateKey, password, certificates.toTypedArray())
+
+    val keyManagerFactory = services.keyManagerFactory()
+    keyManagerFactory.init(keyStore, password)
+    return keyManagerFactory.keyManagers
+}
+
+private fun loadCaTrustStore(
+    certificates: List<Certificate>,
+    services: SecurityServices,
+): KeyStore =
+    services.keyStore(KeyStore.getDefaultType()).apply {
+        load(null, null)
+        certificates.forEachIndexed { index, certificate -> setCertificateEntry("ca-$index", certificate) }
+    }
+
+private fun readCertificates(
+    path: String,
+    services: SecurityServices,
+): List<Certificate> {
+    val file = Paths.get(path)
+    require(Files.isReadable(file)) { "Certificate file not found or not readable: $path" }
+
+    return parseCertificates(Files.readAllBytes(file), path, services)
+}
+
+/**
+ * Parses the PEM encoded certificates in [contents], naming [source] in errors.
+ */
+private fun parseCertificates(
+    contents: ByteArray,
+    source: String,
+    services: SecurityServices,
+): List<Certificate> {
+    val certificates =
+        try {
+            services.certificateFactory().generateCertificates(contents.inputStream()).toList()
+        } catch (e: Exception) {
+            throw IllegalArgumentException("Malformed certificate file: $source", e)
+        }
+
+    require(certificates.isNotEmpty()) { "No certificate found in $source" }
+    return certificates
+}
+
+private fun readPrivateKey(
+    path: String,
+    services: SecurityServices,
+): PrivateKey {
+    val file = Paths.get(path)
+    require(Files.isReadable(file)) { "Private key file not found or not readable: $path" }
+
+    return parsePrivateKey(Files.readAllBytes(file), path, services)
+}
+
+/**
+ * Parses the PEM encoded private key in [contents], naming [source] in errors. [contents] is wiped afterwards.
+ */
+private fun parsePrivateKey(
+    contents: ByteArray,
+    source: String,
+    services: SecurityServices,
+): PrivateKey {
+    // the key is never decoded to a string, which couldn't be wiped, so every buffer holding it is wiped once parsed.
+    // The key spec keeps a copy of its own that can't be wiped, as does the parsed key until it's garbage collected
+    val sensitive = mutableListOf(contents)
+    try {
+        val (type, content) =
+            findPemBlock(contents)
+                ?: throw IllegalArgumentException("Malformed private key file, no PEM block found: $source")
+
+        val der =
+            try {
+                val decoded =
+                    Base64.getMimeDecoder().decode(ByteBuffer.wrap(contents, content.first, content.count()))
+                sensitive += decoded.array()
+                ByteArray(decoded.remaining()).also { der -> decoded.get(der) }.also(sensitive::add)
+            } catch (e: IllegalArgumentException) {
+                throw IllegalArgumentException("Malformed private key file, invalid base64 content: $source", e)
+            }
+
+        val encoded =
+            when (type) {
+                "PRIVATE KEY" -> der
+                "RSA PRIVATE KEY" -> wrapPkcs1RsaKey(der).also(sensitive::add)
+                else -> throw IllegalArgumentException(
+                    "Unsupported private key type \"$type\" in $source, convert it to PKCS#8 with `openssl pkcs8 -topk8 -nocrypt`",
+                )
+            }
+
+        return listOf("RSA", "EC").firstNotNullOfOrNull { algorithm ->
+            runCatching { services.keyFactory(algorithm).generatePrivate(PKCS8EncodedKeySpec(encoded)) }.getOrNull()
+        } ?: throw IllegalArgumentException("Malformed private key file, not a valid RSA or EC key: $source")
+    } finally {
+        sensitive.forEach { buffer -> buffer.fill(0) }
+    }
+}
+
+/**
+ * Finds the first PEM block of [contents] without decoding it to a string, returning its type and the range of its
+ * base64 encoded content.
+ */
+private fun findPemBlock(contents: ByteArray): Pair<String, IntRange>? {
+    val begin = contents.indexOf(PemBeginMarker, from = 0).takeIf { index -> index >= 0 } ?: return null
+    val typeStart = begin + PemBeginMarker.size
+    val typeEnd = contents.indexOf(PemDashes, from = typeStart).takeIf { index -> index > typeStart } ?: return null
+    val isValidType =
+        (typeStart until typeEnd).all { index ->
+            contents[index].toInt().toChar().let { char -> char in 'A'..'Z' || char in '0'..'9' || char == ' ' }
+        }
+    if (!isValidType) return null
+
+    val type = contents.decodeToString(typeStart, typeEnd)
+    val contentStart = typeEnd + PemDashes.size
+    val end = contents.indexOf("-----END $type-----".encodeToByteArray(), from = contentStart)
+    return if (end < 0) null else type to (contentStart until end)
+}
+
+private fun ByteArray.indexOf(
+    bytes: ByteArray,
+    from: Int,
+): Int =
+    (from..size - bytes.size).firstOrNull { index ->
+        bytes.indices.all { offset -> this[index + offset] == bytes[offset] }
+    } ?: -1
+
+/**
+ * Wraps a PKCS#1 RSA private key in a PKCS#8 PrivateKeyInfo structure, the only encoding supported by [KeyFactory].
+ */
+private fun wrapPkcs1RsaKey(pkcs1: ByteArray): ByteArray {
+    val content = ByteArrayOutputStream()
+    content.write(byteArrayOf(0x02, 0x01, 0x00)) // version
+    content.write(RsaAlgorithmIdentifier)
+    content.writeDer(tag = 0x04, pkcs1) // OCTET STRING
+
+    val inner = content.toByteArray()
+    try {
+        return ByteArrayOutputStream()
+            .apply { writeDer(tag = 0x30, inner) } // SEQUENCE
+            .toByteArray()
+    } finally {
+        inner.fill(0)
+    }
+}
+
+private fun ByteArrayOutputStream.writeDer(
+    tag: Int,
+    value: ByteArray,
+) {
+    write(tag)
+    val length = value.size
+    if (length < 0x80) {
+        write(length)
+    } else {
+        val lengthBytes = generateSequence(length) { (it shr 8).takeIf { rest -> rest > 0 } }.toList().reversed()
+        write(0x80 or lengthBytes.size)
+        lengthBytes.forEach { write(it and 0xff) }
+    }
+    write(value)
+}
+
+// the operating system stores are only implemented by the runtime providers, their certificates are still checked by
+// the trust manager of the configured provider
+private fun loadSystemTrustStore(services: SecurityServices): KeyStore {
+    val os = System.getProperty("os.name").lowercase()
+    return when {
+        os.contains("win") -> KeyStore.getInstance("Windows-ROOT").apply { load(null, null) }
+        os.contains("mac") -> loadMacOsTrustStore(services)
+        else -> loadLinuxTrustStore(services)
+    }
+}
+
+private fun loadMacOsTrustStore(services: SecurityServices): KeyStore {
+    // before JDK 23 KeychainStore only reads the login keychain of the user, system roots are in KeychainStore-ROOT
+    val keychains =
+        listOf("KeychainStore", "KeychainStore-ROOT").mapNotNull { type ->
+            runCatching { KeyStore.getInstance(type).apply { load(null, null) } }.getOrNull()
+        }
+
+    require(keychains.isNotEmpty()) { "macOS Keychain is not available in this runtime" }
+    return mergeWithDefaultTrustStore(keychains, services)
+}
+
+/**
+ * Creates a trust store with the trusted certificates of every one of [keyStores] and the root certificates bundled
+ * with the runtime, for system stores that don't hold every root on their own.
+ */
+internal fun mergeWithDefaultTrustStore(
+    keyStores: List<KeyStore>,
+    services: SecurityServices,
+): KeyStore {
+    val trustManagerFactory = services.trustManagerFactory()
+    trustManagerFactory.init(null as KeyStore?)
+    val bundled =
+        trustManagerFactory.trustManagers
+            .filterIsInstance<X509TrustManager>()
+            .flatMap { trustManager -> trustManager.acceptedIssuers.toList() }
+
+    val trusted =
+        keyStores.flatMap { keyStore ->
+            keyStore
+                .aliases()
+                .toList()
+                .filter(keyStore::isCertificateEntry)
+                .mapNotNull(keyStore::getCertificate)
+        }
+
+    return loadCaTrustStore((bundled + trusted).distinct(), services)
+}
+
+private fun loadLinuxTrustStore(services: SecurityServices): KeyStore {
+    val bundle =
+        LinuxCaBundlePaths.map(Paths::get).firstOrNull(Files::isReadable)
+            ?: error("No system CA bundle found, looked at: ${LinuxCaBundlePaths.joinToString()}")
+
+    val certificates =
+        Files.newInputStream(bundle).use { input ->
+            services.certificateFactory().generateCertificates(input)
+        }
+
+    return services.keyStore(KeyStore.getDefaultType()).apply {
+        load(null, null)
+        certificates.forEachIndexed { index, certificate -> setCertificateEntry("system-ca-$index", certificate) }
+    }
+}
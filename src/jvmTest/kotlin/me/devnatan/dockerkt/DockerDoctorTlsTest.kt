@@ -0,0 +1,51 @@
+package me.devnatan.dockerkt
+
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.io.LocalhostCertificate
+import me.devnatan.dockerkt.io.TlsTestServer
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertTrue
+
+class DockerDoctorTlsTest {
+    private suspend fun doctor(
+        port: Int,
+        configure: DockerTlsConfigBuilder.() -> Unit,
+    ): Map<String, DoctorCheck> {
+        val client =
+            createTestDockerClient {
+                socketPath("https://localhost:$port")
+                tls(configure)
+            }
+
+        return try {
+            client.doctor().checks.associateBy(DoctorCheck::name)
+        } finally {
+            client.close()
+        }
+    }
+
+    @Test
+    fun `reports the negotiated tls version and cipher suite`() =
+        runTest {
+            TlsTestServer().use { server ->
+                val checks = doctor(server.port) { caCertificate(LocalhostCertificate).versions(min = TlsVersion.Tls13) }
+
+                val handshake = checks.getValue("TLS handshake")
+                assertEquals(DoctorCheck.Status.Passed, handshake.status, handshake.message)
+                assertTrue(handshake.message.contains("TLSv1.3"), handshake.message)
+                assertEquals(DoctorCheck.Status.Passed, checks.getValue("TLS certificate").status)
+            }
+        }
+
+    @Test
+    fun `reports a rejected daemon certificate separately`() =
+        runTest {
+            TlsTestServer().use { server ->
+                val checks = doctor(server.port) { trustStore(TrustStore.Bundled) }
+
+                assertEquals(DoctorCheck.Status.Skipped, checks.getValue("TLS handshake").status)
+                assertEquals(DoctorCheck.Status.Failed, checks.getValue("TLS certificate").status)
+            }
+        }
+}
@@ -0,0 +1,108 @@
+package me.devnatan.dockerkt.io
+
+import kotlinx.coroutines.test.runTest
+import me.devnatan.dockerkt.DockerResponseInfo
+import me.devnatan.dockerkt.createTestDockerClient
+import me.devnatan.dockerkt.metrics
+import java.io.Closeable
+import java.net.InetAddress
+import java.net.ServerSocket
+import java.util.concurrent.CopyOnWriteArrayList
+import kotlin.concurrent.thread
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertTrue
+
+class CallEventsTest {
+    // HTTP server keeping connections alive, whose responses claim engine details in headers
+    private class KeepAliveServer : Closeable {
+        private val serverSocket = ServerSocket(0, 16, InetAddress.getLoopbackAddress())
+        val port: Int get() = serverSocket.localPort
+        val requestHeaders = CopyOnWriteArrayList<String>()
+
+        init {
+            thread(isDaemon = true) {
+                while (!serverSocket.isClosed) {
+                    val socket = runCatching { serverSocket.accept() }.getOrNull() ?: break
+                    thread(isDaemon = true) {
+                        runCatching {
+                            socket.use {
+                                val input = socket.getInputStream().bufferedReader()
+                                while (input.readLine() != null) {
+                                    while (true) {
+                                        val line = input.readLine()
+                                        if (line.isNullOrEmpty()) break
+                                        requestHeaders += line
+                                    }
+                                    socket.getOutputStream().apply {
+                                        write(
+                                            (
+                                                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nX-Docker-Kotlin-Remote-Address: spoofed\r\n" +
+                                                    "X-Docker-Kotlin-Connection-Reused: true\r\n\r\nOK"
+                                            ).encodeToByteArray(),
+                                        )
+                                        flush()
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        override fun close() {
+            serverSocket.close()
+        }
+    }
+
+    @Test
+    fun `reports connection details without trusting response headers`() =
+        runTest {
+            KeepAliveServer().use { server ->
+                val responses = mutableListOf<DockerResponseInfo>()
+                val client =
+                    createTestDockerClient {
+                        socketPath("tcp://127.0.0.1:${server.port}")
+                        apiVersion("1.41")
+                        responseListener(responses::add)
+                    }
+
+                try {
+                    repeat(2) { client.system.ping(head = false) }
+                } finally {
+                    client.close()
+                }
+
+                assertEquals(listOf(false, true), responses.map(DockerResponseInfo::connectionReused))
+                assertEquals("/127.0.0.1:${server.port}", responses.first().remoteAddress)
+                assertEquals(listOf("spoofed"), responses.first().headerValues("X-Docker-Kotlin-Remote-Address"))
+                assertTrue(server.requestHeaders.none { header -> header.startsWith("X-Docker-Kotlin", ignoreCase = true) })
+            }
+        }
+
+    @Test
+    fun `records connection pool metrics`() =
+        runTest {
+            KeepAliveServer().use { server ->
+                val client =
+                    createTestDockerClient {
+                        socketPath("tcp://127.0.0.1:${server.port}")
+                        apiVersion("1.41")
+                    }
+
+                try {
+                    repeat(2) { client.system.ping(head = false) }
+
+                    val metrics = client.metrics()
+                    assertEquals(2, metrics.connectionsAcquired)
+                    assertEquals(1, metrics.connectionsReused)
+                    assertEquals(0.5, metrics.connectionReuseRate)
+                    assertEquals(4, metrics.bytesReceived)
+                    assertEquals(0, metrics.activeStreams)
+                } finally {
+                    client.close()
+                }
+            }
+        }
+}
@@ -0,0 +1,47 @@
+package me.devnatan.dockerkt.io
+
+import javax.net.SocketFactory
+import kotlin.test.Test
+import kotlin.test.assertFalse
+import kotlin.test.assertNotEquals
+import kotlin.test.assertTrue
+import kotlin.time.Duration.Companion.seconds
+
+class SocketOptionsTest {
+    @Test
+    fun `applies keepalive options`() {
+        val factory =
+            OptionsSocketFactory(
+                SocketFactory.getDefault(),
+                SocketOptions(keepAlive = true, keepAliveIdle = 30.seconds, keepAliveCount = 3),
+            )
+
+        factory.createSocket().use { socket ->
+            assertTrue(socket.keepAlive)
+        }
+    }
+
+    @Test
+    fun `disables Nagle's algorithm by default`() {
+        OptionsSocketFactory(SocketFactory.getDefault(), SocketOptions()).createSocket().use { socket ->
+            assertTrue(socket.tcpNoDelay)
+        }
+
+        OptionsSocketFactory(SocketFactory.getDefault(), SocketOptions(tcpNoDelay = false)).createSocket().use { socket ->
+            assertFalse(socket.tcpNoDelay)
+        }
+    }
+
+    @Test
+    fun `applies buffer sizes`() {
+        val (defaultReceiveBufferSize, defaultSendBufferSize) =
+            SocketFactory.getDefault().createSocket().use { socket -> socket.receiveBufferSize to socket.sendBufferSize }
+
+        // request sizes far from the defaults, the operating system may still adjust the granted ones
+        val options = SocketOptions(receiveBufferSize = defaultReceiveBufferSize / 4, sendBufferSize = defaultSendBufferSize / 4)
+        OptionsSocketFactory(SocketFactory.getDefault(), options).createSocket().use { socket ->
+            assertNotEquals(defaultReceiveBufferSize, socket.receiveBufferSize)
+            assertNotEquals(defaultSendBufferSize, socket.sendBufferSize)
+        }
+    }
+}
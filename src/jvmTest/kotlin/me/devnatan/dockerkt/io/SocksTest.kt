@@ -0,0 +1,88 @@
+package me.devnatan.dockerkt.io
+
+import java.io.DataInputStream
+import java.io.IOException
+import java.net.InetAddress
+import java.net.InetSocketAddress
+import java.net.ServerSocket
+import java.net.SocketTimeoutException
+import kotlin.concurrent.thread
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class SocksTest {
+    // minimal SOCKS5 server that checks the credentials and the requested destination, then answers "pong"
+    private fun socksServer(
+        server: ServerSocket,
+        accept: Boolean,
+        destinations: MutableList<String>,
+    ) = thread(isDaemon = true) {
+        server.accept().use { socket ->
+            val input = DataInputStream(socket.getInputStream())
+            val output = socket.getOutputStream()
+
+            input.readUnsignedByte()
+            input.readFully(ByteArray(input.readUnsignedByte()))
+            output.write(byteArrayOf(5, 2))
+
+            input.readUnsignedByte()
+            val user = ByteArray(input.readUnsignedByte()).also(input::readFully).decodeToString()
+            val password = ByteArray(input.readUnsignedByte()).also(input::readFully).decodeToString()
+            val authenticated = accept && user == "user" && password == "secret"
+            output.write(byteArrayOf(1, if (authenticated) 0 else 1))
+            if (!authenticated) return@thread
+
+            input.readFully(ByteArray(3))
+            check(input.readUnsignedByte() == 3) { "Expected a domain name destination" }
+            val host = ByteArray(input.readUnsignedByte()).also(input::readFully).decodeToString()
+            val port = input.readUnsignedShort()
+            destinations += "$host:$port"
+
+            output.write(byteArrayOf(5, 0, 0, 1, 127, 0, 0, 1, 0, 0))
+            output.write("pong".encodeToByteArray())
+            output.flush()
+        }
+    }
+
+    @Test
+    fun `connects through socks5 proxy with credentials`() {
+        ServerSocket(0, 1, InetAddress.getLoopbackAddress()).use { server ->
+            val destinations = mutableListOf<String>()
+            val serverThread = socksServer(server, accept = true, destinations)
+
+            val proxy = ProxyConfig("127.0.0.1", server.localPort, "user", "secret", type = ProxyType.Socks5h)
+            Socks5SocketFactory(proxy).createSocket().use { socket ->
+                socket.connect(InetSocketAddress.createUnresolved("docker.internal", 2376))
+                assertEquals("pong", socket.getInputStream().readNBytes(4).decodeToString())
+            }
+
+            serverThread.join()
+            assertEquals(listOf("docker.internal:2376"), destinations)
+        }
+    }
+
+    @Test
+    fun `fails on rejected socks5 credentials`() {
+        ServerSocket(0, 1, InetAddress.getLoopbackAddress()).use { server ->
+            socksServer(server, accept = false, mutableListOf())
+
+            val proxy = ProxyConfig("127.0.0.1", server.localPort, "user", "wrong", type = ProxyType.Socks5)
+            assertFailsWith<IOException> {
+                Socks5SocketFactory(proxy).createSocket().connect(InetSocketAddress.createUnresolved("docker.internal", 2376))
+            }
+        }
+    }
+
+    @Test
+    fun `times out when the proxy never answers the handshake`() {
+        ServerSocket(0, 1, InetAddress.getLoopbackAddress()).use { server ->
+            val proxy = ProxyConfig("127.0.0.1", server.localPort, type = ProxyType.Socks5)
+            Socks5SocketFactory(proxy).createSocket().use { socket ->
+                assertFailsWith<SocketTimeoutException> {
+                    socket.connect(InetSocketAddress.createUnresolved("docker.internal", 2376), 200)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+package me.devnatan.dockerkt.io
+
+import kotlinx.coroutines.runBlocking
+import me.devnatan.dockerkt.createTestDockerClient
+import java.net.InetAddress
+import java.net.ServerSocket
+import kotlin.test.Test
+import kotlin.test.assertFails
+import kotlin.test.assertFailsWith
+import kotlin.test.assertTrue
+import kotlin.time.Duration.Companion.milliseconds
+import kotlin.time.Duration.Companion.seconds
+import kotlin.time.TimeSource
+
+class TimeoutsTest {
+    @Test
+    fun `rejects negative timeouts`() {
+        assertFailsWith<IllegalArgumentException> { Timeouts(connect = (-1).seconds) }
+        assertFailsWith<IllegalArgumentException> { Timeouts(readIdle = (-1).seconds) }
+        assertFailsWith<IllegalArgumentException> { Timeouts(total = (-1).seconds) }
+    }
+
+    @Test
+    fun `fails on read idle timeout`() {
+        // accepts connections but never responds
+        ServerSocket(0, 1, InetAddress.getLoopbackAddress()).use { server ->
+            val client =
+                createTestDockerClient {
+                    socketPath("tcp://127.0.0.1:${server.localPort}")
+                    timeouts(readIdle = 200.milliseconds)
+                }
+
+            try {
+                val start = TimeSource.Monotonic.markNow()
+                assertFails {
+                    runBlocking { client.system.ping() }
+                }
+                assertTrue(start.elapsedNow() < 10.seconds, "Took ${start.elapsedNow()} to time out")
+            } finally {
+                client.close()
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+package me.devnatan.dockerkt.io
+
+import java.io.Closeable
+import java.net.InetAddress
+import java.security.KeyFactory
+import java.security.KeyStore
+import java.security.cert.CertificateFactory
+import java.security.spec.PKCS8EncodedKeySpec
+import java.util.Base64
+import javax.net.ssl.KeyManagerFactory
+import javax.net.ssl.SSLContext
+import javax.net.ssl.SSLServerSocket
+import kotlin.concurrent.thread
+
+/**
+ * Self-signed certificate for `localhost` and `127.0.0.1` presented by [TlsTestServer].
+ */
+val LocalhostCertificate: ByteArray = readResource("/tls/localhost.pem")
+
+/**
+ * Public key pin of [LocalhostCertificate].
+ */
+const val LocalhostPublicKeyPin: String = "sha256/RF/D9xfsyZ7rp2SNjT6APE4dOdl16dQgt6H2VT8RE+g="
+
+private fun readResource(name: String): ByteArray =
+    requireNotNull(TlsTestServer::class.java.getResourceAsStream(name)) { "Missing test resource $name" }
+        .use { input -> input.readBytes() }
+
+/**
+ * TLS server on the loopback address presenting [LocalhostCertificate], answering every request with an empty
+ * `200 OK` response.
+ */
+class TlsTestServer : Closeable {
+    private val serverSocket: SSLServerSocket = createServerSocket()
+
+    val port: Int get() = serverSocket.localPort
+
+    init {
+        thread(isDaemon = true) {
+            while (!serverSocket.isClosed) {
+                val socket = runCatching { serverSocket.accept() }.getOrNull() ?: break
+                runCatching {
+                    socket.use {
+                        val input = socket.getInputStream().bufferedReader()
+                        while (!input.readLine().isNullOrEmpty()) {
+                            // skips request headers
+                        }
+                        socket.getOutputStream().apply {
+                            write("HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".encodeToByteArray())
+                            flush()
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    override fun close() {
+        serverSocket.close()
+    }
+
+    private fun createServerSocket(): SSLServerSocket {
+        val certificate = CertificateFactory.getInstance("X.509").generateCertificate(LocalhostCertificate.inputStream())
+        val encodedKey =
+            readResource("/tls/localhost-key.pem")
+                .decodeToString()
+                .lines()
+                .filterNot { line -> line.startsWith("-----") }
+                .joinToString("")
+        val privateKey = KeyFactory.getInstance("RSA").generatePrivate(PKCS8EncodedKeySpec(Base64.getDecoder().decode(encodedKey)))
+
+        val keyStore =
+            KeyStore.getInstance("PKCS12").apply {
+                load(null, null)
+                setKeyEntry("localhost", privateKey, CharArray(0), arrayOf(certificate))
+            }
+        val keyManagerFactory = KeyManagerFactory.getInstance(KeyManagerFactory.getDefaultAlgorithm())
+        keyManagerFactory.init(keyStore, CharArray(0))
+
+        val sslContext = SSLContext.getInstance("TLS")
+        sslContext.init(keyManagerFactory.keyManagers, null, null)
+        return sslContext.serverSocketFactory.createServerSocket(0, 16, InetAddress.getLoopbackAddress()) as SSLServerSocket
+    }
+}
@@ -0,0 +1,58 @@
+package me.devnatan.dockerkt.io
+
+import kotlinx.coroutines.runBlocking
+import me.devnatan.dockerkt.createTestDockerClient
+import java.net.StandardProtocolFamily
+import java.net.UnixDomainSocketAddress
+import java.nio.channels.ServerSocketChannel
+import java.nio.file.Files
+import kotlin.test.Test
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+class UnixSocketTest {
+    private fun pingThrough(socketPath: String): DockerSocketException {
+        val client = createTestDockerClient { socketPath("$UnixSocketPrefix$socketPath") }
+        try {
+            return assertFailsWith<DockerSocketException> {
+                runBlocking { client.system.ping() }
+            }
+        } finally {
+            client.close()
+        }
+    }
+
+    @Test
+    fun `reports missing socket`() {
+        val error = pingThrough("/non/existent/docker.sock")
+        assertEquals(DockerSocketException.Reason.NotFound, error.reason)
+        assertEquals("/non/existent/docker.sock", error.socketPath)
+    }
+
+    @Test
+    fun `reports socket not writable`() {
+        val socketFile = Files.createTempFile("docker-kotlin", ".sock")
+        try {
+            // permissions are not enforced for root
+            if (!socketFile.toFile().setWritable(false) || Files.isWritable(socketFile)) return
+
+            assertEquals(DockerSocketException.Reason.PermissionDenied, pingThrough(socketFile.toString()).reason)
+        } finally {
+            Files.delete(socketFile)
+        }
+    }
+
+    @Test
+    fun `reports stale socket`() {
+        val socketFile = Files.createTempDirectory("docker-kotlin").resolve("docker.sock")
+        try {
+            // the socket file is left behind once the server socket is closed
+            ServerSocketChannel.open(StandardProtocolFamily.UNIX).use { it.bind(UnixDomainSocketAddress.of(socketFile)) }
+
+            assertEquals(DockerSocketException.Reason.ConnectionRefused, pingThrough(socketFile.toString()).reason)
+        } finally {
+            Files.deleteIfExists(socketFile)
+            Files.delete(socketFile.parent)
+        }
+    }
+}
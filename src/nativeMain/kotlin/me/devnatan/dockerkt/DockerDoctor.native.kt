@@ -0,0 +1,14 @@
+package me.devnatan.dockerkt
+
+import kotlin.time.Duration
+
+internal actual fun isReadableAndWritable(path: String): Boolean? = null
+
+internal actual suspend fun resolveHostAddresses(host: String): List<String>? = null
+
+internal actual suspend fun performTlsHandshake(
+    host: String,
+    port: Int,
+    tls: DockerTlsConfig,
+    connectTimeout: Duration,
+): TlsHandshakeResult? = null
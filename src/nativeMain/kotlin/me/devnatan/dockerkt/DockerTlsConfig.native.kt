@@ -0,0 +1,3 @@
+package me.devnatan.dockerkt
+
+internal actual fun isFipsSecurityProvider(name: String): Boolean = false
@@ -3,29 +3,63 @@ package me.devnatan.dockerkt.io
 import io.ktor.client.HttpClientConfig
 import io.ktor.client.engine.HttpClientEngineConfig
 import io.ktor.client.engine.HttpClientEngineFactory
+import io.ktor.client.engine.ProxyBuilder
 import io.ktor.client.engine.cio.CIO
+import io.ktor.client.plugins.HttpTimeoutConfig
+import io.ktor.client.plugins.api.createClientPlugin
 import io.ktor.client.plugins.defaultRequest
 import io.ktor.client.request.header
 import io.ktor.http.HttpHeaders
+import io.ktor.http.URLBuilder
+import io.ktor.http.isWebsocket
 import me.devnatan.dockerkt.DockerClient
+import kotlin.time.Duration
 
 internal actual val defaultHttpClientEngine: HttpClientEngineFactory<*>? get() = CIO
 
+internal actual val engineReportsCallInfo: Boolean get() = false
+
 internal actual fun <T : HttpClientEngineConfig> HttpClientConfig<out T>.configureHttpClient(client: DockerClient) {
+    check(client.config.tls == null) { "TLS is only supported on the JVM for now" }
+    val endpoint = DockerEndpoint.parse(client.config.socketPath)
+    check(endpoint !is DockerEndpoint.NamedPipe) { "Named pipes are only supported on the JVM for now" }
+
+    val proxyConfig = client.config.proxy.resolveFor(client.config.socketPath)
+    check(proxyConfig == null || proxyConfig.type == ProxyType.Http) { "SOCKS proxies are only supported on the JVM for now" }
     engine {
         require(this is io.ktor.client.engine.cio.CIOEngineConfig) { "Only CIO engine is supported for now" }
-        // disable request timeout so long-running calls (image pulls, log streams) aren't killed
-        requestTimeout = 0
+        // no timeout by default so long-running calls (image pulls, log streams) aren't killed
+        val timeouts = client.config.timeouts
+        requestTimeout = timeouts.total.inWholeMilliseconds
+        endpoint {
+            connectTimeout = timeouts.connect.toTimeoutMillis()
+            socketTimeout = timeouts.readIdle.toTimeoutMillis()
+        }
+        proxy = proxyConfig?.let { ProxyBuilder.http(URLBuilder(host = it.host, port = it.port).build()) }
     }
     defaultRequest {
-        val socketPath = client.config.socketPath
-        if (isUnixSocket(socketPath)) {
-            unixSocket(socketPath.removePrefix(UnixSocketPrefix))
+        if (endpoint is DockerEndpoint.Unix) {
+            unixSocket(endpoint.path)
         }
-        // Force Connection: close. Docker often returns bodies framed by connection-close (no
-        // Content-Length, no Transfer-Encoding), and Ktor CIO over unix sockets cannot otherwise
-        // determine where the response ends — throwing "request body length should be specified,
-        // chunked transfer encoding should be used or keep-alive should be disabled (connection: close)".
-        header(HttpHeaders.Connection, "close")
+        proxyConfig?.basicAuthorization()?.let { authorization -> header(HttpHeaders.ProxyAuthorization, authorization) }
     }
+    install(ConnectionClose)
 }
+
+// CIO only disables its endpoint timeouts when set to infinite, zero would make them expire right away
+private fun Duration.toTimeoutMillis(): Long =
+    if (this == Duration.ZERO) HttpTimeoutConfig.INFINITE_TIMEOUT_MS else inWholeMilliseconds
+
+// Force Connection: close. Docker often returns bodies framed by connection-close (no
+// Content-Length, no Transfer-Encoding), and Ktor CIO over unix sockets cannot otherwise
+// determine where the response ends — throwing "request body length should be specified,
+// chunked transfer encoding should be used or keep-alive should be disabled (connection: close)".
+// WebSocket handshakes are left alone since they need Connection: Upgrade.
+private val ConnectionClose =
+    createClientPlugin("ConnectionClose") {
+        onRequest { request, _ ->
+            if (!request.url.protocol.isWebsocket()) {
+                request.header(HttpHeaders.Connection, "close")
+            }
+        }
+    }